@@ -0,0 +1,143 @@
+/*
+    Same conditions for PostgreSQL and MySQL, there is no database in tests so SQL is checked with
+    debug_query
+*/
+
+use super::*;
+
+table! {
+    item (id) {
+        id -> Text,
+        name -> Text,
+        description -> Nullable<Text>,
+        count -> Integer,
+        active -> Bool,
+        created -> Timestamp,
+    }
+}
+
+// Boxed conditions and queries are boxed for one backend, so the generated items are per backend,
+// a table used with several backends declares them once and expands them in a module per backend
+#[allow(unused_macros)]
+macro_rules! item_filters {
+    ($backend:ty) => {
+        use super::*;
+
+        dynamic_filter! {
+            pub(super) Condition,
+            Backend = $backend,
+            ConditionSource = item::dsl::item,
+            QuerySource = item::dsl::item,
+            query = item::dsl::item,
+            fields {
+                name(StringFilter) => |f| string_filter!(f, item::dsl::name),
+                description(StringFilter) => |f| string_filter!(f, item::dsl::description),
+                count(NumberFilter<i32>) => |f| number_filter!(f, item::dsl::count),
+                active(BooleanFilter) => |f| boolean_filter!(f, item::dsl::active),
+                created(DateTimeFilter<NaiveDateTime>) => |f, options| {
+                    date_filter!(f, item::dsl::created, options)
+                },
+            }
+        }
+
+        dynamic_sort! {
+            pub(super) Sort,
+            primary_key = [id],
+            fields {
+                id(String) => item::dsl::id,
+                description(String) => item::dsl::description,
+            }
+        }
+    };
+}
+
+#[cfg(feature = "postgres")]
+mod pg {
+    item_filters!(diesel::pg::Pg);
+}
+
+#[cfg(feature = "mysql")]
+mod mysql {
+    item_filters!(diesel::mysql::Mysql);
+}
+
+#[cfg(feature = "postgres")]
+#[test]
+fn postgres_test() {
+    use diesel::{debug_query, pg::Pg};
+    use pg::*;
+
+    let condition = vec![
+        Condition::description(StringFilter::ContainsIgnoreCase("50%".to_string())),
+        Condition::Or(vec![
+            Condition::count(NumberFilter::In(vec![1, 2])),
+            Condition::Not(Box::new(Condition::name(StringFilter::EqualIgnoreCase(
+                "Bike".to_string(),
+            )))),
+        ]),
+    ];
+    let query = create_filtered_query(condition)
+        .unwrap()
+        .select(item::dsl::id);
+
+    assert_eq!(
+        debug_query::<Pg, _>(&query).to_string(),
+        r#"SELECT "item"."id" FROM "item" WHERE (("item"."description" ILIKE $1 ESCAPE $2) AND (("item"."count" = ANY($3)) OR  NOT ((lower("item"."name") = lower($4))))) -- binds: ["%50\\%%", "\\", [1, 2], "Bike"]"#
+    );
+
+    // Keyset condition after description NULL, first when ascending as in ORDER BY (PostgreSQL
+    // would put it last)
+    let request = ListRequest {
+        filter: vec![],
+        sort: vec![Sort::asc(SortField::description)],
+    };
+    let query = create_page_query(request, Some("n.v6931"), 10)
+        .unwrap()
+        .select(item::dsl::id);
+
+    assert_eq!(
+        debug_query::<Pg, _>(&query).to_string(),
+        r#"SELECT "item"."id" FROM "item" WHERE (("item"."description" IS NOT NULL) OR (("item"."description" IS NULL) AND ("item"."id" > $1))) ORDER BY ("item"."description" IS NULL) DESC , "item"."description" ASC , ("item"."id" IS NULL) DESC , "item"."id" ASC  LIMIT $2 -- binds: ["i1", 11]"#
+    );
+}
+
+#[cfg(feature = "mysql")]
+#[test]
+fn mysql_test() {
+    use diesel::{debug_query, mysql::Mysql};
+    use mysql::*;
+
+    let condition = vec![
+        Condition::description(StringFilter::ContainsIgnoreCase("50%".to_string())),
+        Condition::created(DateTimeFilter::Between {
+            from: NaiveDate::from_ymd_opt(2026, 10, 1)
+                .unwrap()
+                .and_time(NaiveTime::MIN),
+            to: NaiveDate::from_ymd_opt(2026, 10, 18)
+                .unwrap()
+                .and_time(NaiveTime::MIN),
+        }),
+    ];
+    let query = create_filtered_query(condition)
+        .unwrap()
+        .select(item::dsl::id);
+
+    assert_eq!(
+        debug_query::<Mysql, _>(&query).to_string(),
+        r#"SELECT `item`.`id` FROM `item` WHERE ((lower(`item`.`description`) LIKE lower(?) ESCAPE ?) AND ((`item`.`created` >= ?) AND (`item`.`created` <= ?))) -- binds: ["%50\\%%", "\\", 2026-10-01T00:00:00, 2026-10-18T00:00:00]"#
+    );
+
+    // Keyset condition after description "a" when descending, NULLs come last
+    let request = ListRequest {
+        filter: vec![],
+        sort: vec![Sort::desc(SortField::description)],
+    };
+    let query = create_page_query(request, Some("v61.v6931"), 10)
+        .unwrap()
+        .select(item::dsl::id);
+
+    assert_eq!(
+        debug_query::<Mysql, _>(&query).to_string(),
+        r#"SELECT `item`.`id` FROM `item` WHERE (((`item`.`description` < ?) OR (`item`.`description` IS NULL)) OR ((`item`.`description` = ?) AND (`item`.`id` > ?))) ORDER BY (`item`.`description` IS NULL) ASC , `item`.`description` DESC , (`item`.`id` IS NULL) DESC , `item`.`id` ASC  LIMIT ? -- binds: ["a", "a", "i1", 11]"#
+    );
+}
@@ -5,11 +5,8 @@
 
 use super::*;
 use diesel::{
-    connection::SimpleConnection,
-    helper_types::LeftJoinQuerySource,
+    helper_types::{LeftJoin, LeftJoinQuerySource},
     prelude::*,
-    sql_types::{Bool, Nullable},
-    sqlite::Sqlite,
 };
 
 table! {
@@ -17,7 +14,13 @@ table! {
         id -> Text,
         number_field -> Integer,
         text_field -> Text,
-        bool_field -> Bool
+        bool_field -> Bool,
+        date_field -> Nullable<Date>,
+        time_field -> Nullable<Time>,
+        timestamp_field -> Nullable<Timestamp>,
+        nullable_number_field -> Nullable<Integer>,
+        nullable_text_field -> Nullable<Text>,
+        nullable_bool_field -> Nullable<Bool>,
     }
 }
 
@@ -32,63 +35,139 @@ table! {
 joinable!(join_to_test -> test (test_id));
 allow_tables_to_appear_in_same_query!(test, join_to_test);
 
-#[allow(non_camel_case_types)]
-enum Condition {
-    number_field(NumberFilter<i32>),
-    double_field(NumberFilter<f64>),
-    text_field(StringFilter),
-    bool_field(BooleanFilter),
-    And(Vec<Condition>),
-    Or(Vec<Condition>),
+dynamic_filter! {
+    Condition,
+    Backend = diesel::sqlite::Sqlite,
+    ConditionSource = LeftJoinQuerySource<test::dsl::test, join_to_test::dsl::join_to_test>,
+    QuerySource = LeftJoin<test::dsl::test, join_to_test::dsl::join_to_test>,
+    query = test::dsl::test.left_join(join_to_test::dsl::join_to_test),
+    fields {
+        number_field(NumberFilter<i32>) => |f| number_filter!(f, test::dsl::number_field),
+        double_field(NumberFilter<f64>) => |f| number_filter!(f, join_to_test::dsl::double_field),
+        text_field(StringFilter) => |f| string_filter!(f, test::dsl::text_field),
+        bool_field(BooleanFilter) => |value| boolean_filter!(value, test::dsl::bool_field),
+        date_field(DateTimeFilter<NaiveDate>) => |f, options| {
+            date_filter!(f, test::dsl::date_field, options)
+        },
+        time_field(DateTimeFilter<NaiveTime>) => |f, options| {
+            date_filter!(f, test::dsl::time_field, options)
+        },
+        timestamp_field(DateTimeFilter<NaiveDateTime>) => |f, options| {
+            date_filter!(f, test::dsl::timestamp_field, options)
+        },
+        nullable_number_field(NumberFilter<i32>) => |f| {
+            number_filter!(f, test::dsl::nullable_number_field)
+        },
+        nullable_text_field(StringFilter) => |f| string_filter!(f, test::dsl::nullable_text_field),
+        nullable_bool_field(BooleanFilter) => |f| boolean_filter!(f, test::dsl::nullable_bool_field),
+    }
 }
 
-type Source = LeftJoinQuerySource<test::dsl::test, join_to_test::dsl::join_to_test>;
-// Need this type for common condition expressions
-type BoxedCondition = Box<dyn BoxableExpression<Source, Sqlite, SqlType = Nullable<Bool>>>;
-
-impl Condition {
-    fn to_boxed_condition(self) -> Option<BoxedCondition> {
-        Some(match self {
-            Condition::number_field(f) => number_filter!(f, test::dsl::number_field),
-            Condition::double_field(f) => number_filter!(f, join_to_test::dsl::double_field),
-            Condition::text_field(f) => string_filter!(f, test::dsl::text_field),
-            Condition::bool_field(value) => boolean_filter!(value, test::dsl::bool_field),
-            Condition::And(conditions) => match create_filter(conditions, AndOr::And) {
-                Some(boxed_condition) => boxed_condition,
-                None => return None,
-            },
-            Condition::Or(conditions) => match create_filter(conditions, AndOr::Or) {
-                Some(boxed_condition) => boxed_condition,
-                None => return None,
-            },
+#[allow(non_snake_case)]
+fn create__and_filter(conditions: Vec<Condition>) -> Result<BoxedCondition, FilterError> {
+    create_filter(conditions, AndOr::And, &FilterOptions::default())
+}
+
+// Row of test left joined to join_to_test, to evaluate conditions in memory
+#[derive(Queryable, Debug)]
+struct TestRow {
+    id: String,
+    number_field: i32,
+    text_field: String,
+    bool_field: bool,
+    date_field: Option<NaiveDate>,
+    time_field: Option<NaiveTime>,
+    timestamp_field: Option<NaiveDateTime>,
+    nullable_number_field: Option<i32>,
+    nullable_text_field: Option<String>,
+    nullable_bool_field: Option<bool>,
+    // Null when there is no join_to_test row
+    double_field: Option<f64>,
+}
+
+impl EvaluateCondition for Field {
+    type Row = TestRow;
+
+    fn evaluate_with(
+        &self,
+        row: &TestRow,
+        options: &FilterOptions,
+    ) -> Result<Option<bool>, FilterError> {
+        Ok(match self {
+            Field::number_field(f) => f.evaluate(Some(&row.number_field)),
+            Field::double_field(f) => f.evaluate(row.double_field.as_ref()),
+            Field::text_field(f) => f.evaluate(Some(&row.text_field)),
+            Field::bool_field(f) => f.evaluate(Some(row.bool_field)),
+            Field::date_field(f) => f.evaluate(row.date_field.as_ref(), options)?,
+            Field::time_field(f) => f.evaluate(row.time_field.as_ref(), options)?,
+            Field::timestamp_field(f) => f.evaluate(row.timestamp_field.as_ref(), options)?,
+            Field::nullable_number_field(f) => f.evaluate(row.nullable_number_field.as_ref()),
+            Field::nullable_text_field(f) => f.evaluate(row.nullable_text_field.as_deref()),
+            Field::nullable_bool_field(f) => f.evaluate(row.nullable_bool_field),
         })
     }
 }
 
-// This method can also be made into a macro, but it should be fine to just duplicate
-fn create_filter(conditions: Vec<Condition>, and_or: AndOr) -> Option<BoxedCondition> {
-    conditions
-        .into_iter()
-        // Map into array of boxed conditions
-        .filter_map::<BoxedCondition, _>(Condition::to_boxed_condition)
-        // Reduce to a boxed_condition1.and(boxed_condition2).and(boxed_condition3)...
-        .fold(None, |boxed_conditions, boxed_condition| {
-            Some(match boxed_conditions {
-                Some(bc) => match and_or {
-                    AndOr::And => Box::new(bc.and(boxed_condition)),
-                    AndOr::Or => Box::new(bc.or(boxed_condition)),
-                },
-                None => boxed_condition,
-            })
-        })
+#[cfg(test)]
+const SCHEMA: &str = r#"
+    CREATE TABLE test (
+        id TEXT PRIMARY KEY,
+        number_field NUMBER NOT NULL,
+        text_field TEXT NOT NULL DEFAULT '',
+        bool_field BOOL NOT NULL DEFAULT false,
+        date_field DATE,
+        time_field TIME,
+        timestamp_field TIMESTAMP,
+        nullable_number_field NUMBER,
+        nullable_text_field TEXT,
+        nullable_bool_field BOOL
+    );
+
+    CREATE TABLE join_to_test (
+        id TEXT PRIMARY KEY,
+        test_id TEXT REFERENCES test(id),
+        double_field DOUBLE NOT NULL
+    );
+"#;
+
+// New in memory database with test schema and rows
+#[cfg(test)]
+fn connection_with_rows(rows: &str) -> SqliteConnection {
+    use diesel::connection::SimpleConnection;
+
+    let mut connection = SqliteConnection::establish(":memory:").unwrap();
+    connection.batch_execute(SCHEMA).unwrap();
+    connection.batch_execute(rows).unwrap();
+    connection
 }
 
-fn create__and_filter(conditions: Vec<Condition>) -> Option<BoxedCondition> {
-    create_filter(conditions, AndOr::And)
+// Ids of rows matched by conditions, id is repeated for every join_to_test row
+#[cfg(test)]
+fn load_ids(connection: &mut SqliteConnection, conditions: Vec<Condition>) -> Vec<String> {
+    load_ids_with(connection, conditions, &FilterOptions::default()).unwrap()
+}
+
+#[cfg(test)]
+fn load_ids_with(
+    connection: &mut SqliteConnection,
+    conditions: Vec<Condition>,
+    options: &FilterOptions,
+) -> Result<Vec<String>, FilterError> {
+    let condition = create_filter(conditions, AndOr::And, options)?;
+
+    Ok(test::dsl::test
+        .left_join(join_to_test::dsl::join_to_test)
+        .filter(condition)
+        .select(test::dsl::id)
+        .order_by((test::dsl::id, join_to_test::dsl::id))
+        .load::<String>(connection)
+        .unwrap())
 }
 
 #[test]
 fn test() {
+    use diesel::connection::SimpleConnection;
+
     let mut connection = SqliteConnection::establish("file:test?mode=memory&cache=shared").unwrap();
 
     connection
@@ -359,3 +438,1344 @@ fn test() {
             .unwrap()
     );
 }
+
+// Same rows as in test()
+#[cfg(test)]
+const NUMBER_ROWS: &str = r#"
+    INSERT INTO test
+      (id, number_field, text_field, bool_field)
+    VALUES
+      ('1', 1, '', false),
+      ('2', 2, '', false),
+      ('3', 3, '', false),
+      ('4.1', 4, '4.1', false),
+      ('4.2', 4, '4.2', true),
+      ('5', 5, '', true),
+      ('6', 6, '', false),
+      ('7.1', 7, '', false),
+      ('7.2', 7, '', false);
+
+    INSERT INTO join_to_test
+      (id, test_id, double_field)
+    VALUES
+      ('1', '6', 1.2),
+      ('7', '7.1', 0);
+"#;
+
+#[test]
+fn not_test() {
+    let mut connection = connection_with_rows(NUMBER_ROWS);
+
+    // Three-valued logic: double_field = 1.2 is NULL for rows without join_to_test, and NOT NULL
+    // is still NULL, thus only 7.1 matches
+    let conditions = vec![Condition::Not(Box::new(Condition::double_field(
+        NumberFilter::Equal(1.2),
+    )))];
+    assert_eq!(vec!["7.1"], load_ids(&mut connection, conditions));
+
+    let conditions = vec![
+        Condition::Not(Box::new(Condition::double_field(NumberFilter::IsNull))),
+        Condition::number_field(NumberFilter::Equal(7)),
+    ];
+    assert_eq!(vec!["7.1"], load_ids(&mut connection, conditions));
+
+    // NOT (number_field = 7 OR number_field < 5)
+    let conditions = vec![Condition::Not(Box::new(Condition::Or(vec![
+        Condition::number_field(NumberFilter::Equal(7)),
+        Condition::number_field(NumberFilter::LowerThen(5)),
+    ])))];
+    assert_eq!(vec!["5", "6"], load_ids(&mut connection, conditions));
+}
+
+#[test]
+fn empty_group_test() {
+    let mut connection = connection_with_rows(NUMBER_ROWS);
+
+    // Empty Or is FALSE, deselecting every option of Or group should not match all rows
+    let conditions = vec![
+        Condition::number_field(NumberFilter::Equal(5)),
+        Condition::Or(vec![]),
+    ];
+    assert_eq!(Vec::<String>::new(), load_ids(&mut connection, conditions));
+
+    // Empty And is TRUE, and NOT (empty Or) is TRUE
+    let conditions = vec![
+        Condition::number_field(NumberFilter::Equal(5)),
+        Condition::And(vec![]),
+        Condition::Not(Box::new(Condition::Or(vec![]))),
+        Condition::True,
+    ];
+    assert_eq!(vec!["5"], load_ids(&mut connection, conditions));
+
+    let conditions = vec![Condition::Or(vec![
+        Condition::number_field(NumberFilter::Equal(5)),
+        Condition::False,
+    ])];
+    assert_eq!(vec!["5"], load_ids(&mut connection, conditions));
+
+    let options = FilterOptions {
+        empty_group: EmptyGroup::Reject,
+        ..Default::default()
+    };
+    let conditions = vec![
+        Condition::number_field(NumberFilter::Equal(5)),
+        Condition::Not(Box::new(Condition::Or(vec![]))),
+    ];
+    assert_eq!(
+        Err(FilterError::EmptyGroup),
+        load_ids_with(&mut connection, conditions, &options)
+    );
+}
+
+// Number filter ranges and lists
+#[test]
+fn number_filter_test() {
+    let mut connection = connection_with_rows(NUMBER_ROWS);
+
+    let cases = vec![
+        (
+            vec![
+                Condition::number_field(NumberFilter::GreaterOrEqual(6)),
+                Condition::number_field(NumberFilter::LowerOrEqual(7)),
+            ],
+            vec!["6", "7.1", "7.2"],
+        ),
+        (
+            vec![Condition::number_field(NumberFilter::Between {
+                from: 2,
+                to: 4,
+                from_inclusive: true,
+                to_inclusive: false,
+            })],
+            vec!["2", "3"],
+        ),
+        (
+            vec![Condition::number_field(NumberFilter::Between {
+                from: 2,
+                to: 4,
+                from_inclusive: false,
+                to_inclusive: true,
+            })],
+            vec!["3", "4.1", "4.2"],
+        ),
+        (
+            vec![Condition::number_field(NumberFilter::In(vec![1, 5]))],
+            vec!["1", "5"],
+        ),
+        (
+            vec![Condition::number_field(NumberFilter::NotIn(vec![
+                1, 2, 3, 4, 5,
+            ]))],
+            vec!["6", "7.1", "7.2"],
+        ),
+        (
+            vec![Condition::double_field(NumberFilter::Between {
+                from: 0.0,
+                to: 1.2,
+                from_inclusive: false,
+                to_inclusive: true,
+            })],
+            vec!["6"],
+        ),
+        (
+            vec![Condition::double_field(NumberFilter::In(vec![0.0]))],
+            vec!["7.1"],
+        ),
+    ];
+
+    for (conditions, result) in cases {
+        assert_eq!(result, load_ids(&mut connection, conditions));
+    }
+}
+
+#[test]
+fn date_time_test() {
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, date_field, time_field, timestamp_field)
+        VALUES
+          ('d1', 1, '2026-10-18', '09:30:00', '2026-10-18 09:30:00'),
+          ('d2', 2, '2026-10-12', '11:00:00', '2026-10-12 11:00:00'),
+          ('d3', 3, '2026-09-30', '23:59:59', '2026-09-30 23:59:59'),
+          ('d4', 4, NULL, NULL, NULL);
+    "#,
+    );
+
+    // Sunday
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+    let options = FilterOptions {
+        clock: now,
+        ..Default::default()
+    };
+    let date = |day| NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
+    let time = |hour, minute| NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+
+    let cases = vec![
+        (
+            Condition::timestamp_field(DateTimeFilter::Before(date(1).and_time(NaiveTime::MIN))),
+            vec!["d3"],
+        ),
+        (
+            Condition::timestamp_field(DateTimeFilter::After(date(12).and_time(time(11, 0)))),
+            vec!["d1"],
+        ),
+        (
+            Condition::timestamp_field(DateTimeFilter::Between {
+                from: date(12).and_time(time(11, 0)),
+                to: date(18).and_time(time(9, 30)),
+            }),
+            vec!["d1", "d2"],
+        ),
+        (
+            Condition::timestamp_field(DateTimeFilter::OnDay(date(12))),
+            vec!["d2"],
+        ),
+        (
+            Condition::timestamp_field(DateTimeFilter::Last(7, TimeUnit::Days)),
+            vec!["d1", "d2"],
+        ),
+        (
+            Condition::timestamp_field(DateTimeFilter::OlderThan(3, TimeUnit::Hours)),
+            vec!["d2", "d3"],
+        ),
+        (
+            Condition::timestamp_field(DateTimeFilter::This(Period::Week)),
+            vec!["d1", "d2"],
+        ),
+        (
+            Condition::timestamp_field(DateTimeFilter::This(Period::Month)),
+            vec!["d1", "d2"],
+        ),
+        (
+            Condition::timestamp_field(DateTimeFilter::IsNull),
+            vec!["d4"],
+        ),
+        (
+            Condition::date_field(DateTimeFilter::Equal(date(12))),
+            vec!["d2"],
+        ),
+        (
+            Condition::date_field(DateTimeFilter::Last(6, TimeUnit::Days)),
+            vec!["d1", "d2"],
+        ),
+        (
+            Condition::date_field(DateTimeFilter::OlderThan(7, TimeUnit::Days)),
+            vec!["d3"],
+        ),
+        (
+            Condition::date_field(DateTimeFilter::This(Period::Year)),
+            vec!["d1", "d2", "d3"],
+        ),
+        (
+            Condition::time_field(DateTimeFilter::Before(time(10, 0))),
+            vec!["d1"],
+        ),
+        (
+            Condition::time_field(DateTimeFilter::Last(3, TimeUnit::Hours)),
+            vec!["d1", "d2"],
+        ),
+    ];
+
+    for (condition, result) in cases {
+        assert_eq!(
+            result,
+            load_ids_with(&mut connection, vec![condition], &options).unwrap()
+        );
+    }
+
+    // Whole day is not a time of day range
+    assert_eq!(
+        Some(FilterError::InvalidDateTimeRange),
+        create_filter(
+            vec![Condition::time_field(DateTimeFilter::This(Period::Day))],
+            AndOr::And,
+            &options
+        )
+        .err()
+    );
+}
+
+#[test]
+fn load_page_test() {
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, timestamp_field)
+        VALUES
+          ('d1', 1, '2026-10-18 09:30:00'),
+          ('d2', 2, '2026-10-12 11:00:00');
+    "#,
+    );
+
+    // A day later every time it's read
+    static DAYS: AtomicI64 = AtomicI64::new(0);
+    fn clock() -> NaiveDateTime {
+        let days = DAYS.fetch_add(1, Ordering::SeqCst);
+        NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            + chrono::Duration::days(days)
+    }
+    let options = FilterOptions {
+        clock,
+        ..Default::default()
+    };
+
+    // Count and items see the same now
+    let conditions = vec![Condition::timestamp_field(DateTimeFilter::Last(
+        1,
+        TimeUnit::Days,
+    ))];
+    let page = load_page_with(&mut connection, conditions, &options, 10, 0, |query| {
+        query.select(test::dsl::id).order_by(test::dsl::id)
+    })
+    .unwrap();
+
+    assert_eq!(
+        Page {
+            items: vec!["d1".to_string()],
+            total: 1,
+            limit: 10,
+            offset: 0,
+        },
+        page
+    );
+    assert_eq!(1, DAYS.load(Ordering::SeqCst));
+}
+
+#[test]
+fn string_filter_test() {
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, text_field)
+        VALUES
+          ('percent_sign', 1, '50%'),
+          ('percent_word', 2, '50 percent'),
+          ('underscore', 3, 'Abc_def'),
+          ('no_underscore', 4, 'abcXdef'),
+          ('backslash', 5, 'A\b'),
+          ('accent', 6, 'café');
+    "#,
+    );
+
+    let cases = vec![
+        (
+            StringFilter::Contains("50%".to_string()),
+            vec!["percent_sign"],
+        ),
+        (
+            StringFilter::Like("50%".to_string()),
+            vec!["percent_sign", "percent_word"],
+        ),
+        (
+            StringFilter::NotLike("50%".to_string()),
+            vec!["accent", "backslash", "no_underscore", "underscore"],
+        ),
+        (
+            StringFilter::StartsWith("abc_".to_string()),
+            vec!["underscore"],
+        ),
+        (StringFilter::EndsWith("\\b".to_string()), vec!["backslash"]),
+        (
+            StringFilter::NotIn(vec!["50%".to_string(), "café".to_string()]),
+            vec!["backslash", "no_underscore", "percent_word", "underscore"],
+        ),
+        (
+            StringFilter::EqualIgnoreCase("ABC_DEF".to_string()),
+            vec!["underscore"],
+        ),
+        (StringFilter::Equal("ABC_DEF".to_string()), vec![]),
+        (
+            StringFilter::ContainsIgnoreCase("C_D".to_string()),
+            vec!["underscore"],
+        ),
+        (
+            StringFilter::InIgnoreCase(vec!["abcxDEF".to_string(), "50 PERCENT".to_string()]),
+            vec!["no_underscore", "percent_word"],
+        ),
+        (StringFilter::InIgnoreCase(vec![]), vec![]),
+        // Same value regardless of non ASCII characters (that SQLite lower() leaves as is)
+        (
+            StringFilter::EqualIgnoreCase("café".to_string()),
+            vec!["accent"],
+        ),
+    ];
+
+    for (filter, result) in cases {
+        assert_eq!(
+            result,
+            load_ids(&mut connection, vec![Condition::text_field(filter)])
+        );
+    }
+}
+
+#[test]
+fn nullable_test() {
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, nullable_number_field, nullable_text_field, nullable_bool_field)
+        VALUES
+          ('values', 1, 1, 'Some Text', true),
+          ('nulls', 2, NULL, NULL, NULL);
+    "#,
+    );
+
+    let cases = vec![
+        (
+            Condition::nullable_number_field(NumberFilter::In(vec![1, 2])),
+            vec!["values"],
+        ),
+        (
+            Condition::nullable_number_field(NumberFilter::IsNull),
+            vec!["nulls"],
+        ),
+        (
+            Condition::nullable_text_field(StringFilter::ContainsIgnoreCase("text".to_string())),
+            vec!["values"],
+        ),
+        (
+            Condition::nullable_text_field(StringFilter::InIgnoreCase(vec![
+                "some text".to_string()
+            ])),
+            vec!["values"],
+        ),
+        // NULL is not equal and not not equal to a value
+        (
+            Condition::nullable_text_field(StringFilter::NotEqual("Some Text".to_string())),
+            vec![],
+        ),
+        (
+            Condition::nullable_text_field(StringFilter::IsNull),
+            vec!["nulls"],
+        ),
+        (
+            Condition::nullable_text_field(StringFilter::IsNotNull),
+            vec!["values"],
+        ),
+        (Condition::text_field(StringFilter::IsNull), vec![]),
+        (
+            Condition::nullable_bool_field(BooleanFilter::True),
+            vec!["values"],
+        ),
+        (
+            Condition::nullable_bool_field(BooleanFilter::IsNull),
+            vec!["nulls"],
+        ),
+    ];
+
+    for (condition, result) in cases {
+        assert_eq!(result, load_ids(&mut connection, vec![condition]));
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_test() {
+    use serde_json::json;
+
+    let json = json!([
+        {"number_field": {"gte": 2}},
+        {"or": [
+            {"text_field": {"like": "4%"}},
+            {"bool_field": "true"},
+        ]},
+        {"not": {"double_field": "is_null"}},
+        {"and": [
+            {"number_field": {"between": {"from": 1, "to": 5, "from_inclusive": true, "to_inclusive": false}}},
+            {"timestamp_field": {"last": [7, "days"]}},
+            {"date_field": {"this": "month"}},
+        ]},
+        "true",
+    ]);
+
+    let conditions: Vec<Condition> = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(json, serde_json::to_value(&conditions).unwrap());
+
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, text_field, bool_field)
+        VALUES
+          ('1', 1, '', true),
+          ('2', 2, '', true),
+          ('3', 3, '4.3', false),
+          ('4', 4, '', false);
+
+        INSERT INTO join_to_test
+          (id, test_id, double_field)
+        VALUES
+          ('2', '2', 1.0),
+          ('3', '3', 1.0),
+          ('4', '4', 1.0);
+    "#,
+    );
+
+    let json = json!([
+        {"number_field": {"gte": 2}},
+        {"or": [
+            {"text_field": {"like": "4%"}},
+            {"bool_field": "true"},
+        ]},
+        {"not": {"double_field": "is_null"}},
+    ]);
+    let conditions: Vec<Condition> = serde_json::from_value(json).unwrap();
+    let result = vec!["2".to_string(), "3".to_string()];
+
+    assert_eq!(result, load_ids(&mut connection, conditions));
+
+    // Unknown operator
+    assert!(serde_json::from_value::<Condition>(json!({"number_field": {"like": 1}})).is_err());
+}
+
+#[test]
+fn text_syntax_test() {
+    use crate::syntax::{text, SyntaxError, SyntaxErrorKind};
+    let input = r#"number_field >= 2 and (text_field like "4%" or bool_field = true) and not double_field is null"#;
+    let conditions: Vec<Condition> = text::parse(input).unwrap();
+    assert_eq!(text::print(&conditions), input);
+
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, text_field, bool_field, date_field)
+        VALUES
+          ('1', 1, '', true, '2026-10-18'),
+          ('2', 2, '', true, NULL),
+          ('3', 3, '4.3', false, '2026-10-17'),
+          ('4', 4, '', false, NULL);
+
+        INSERT INTO join_to_test
+          (id, test_id, double_field)
+        VALUES
+          ('2', '2', 1.0),
+          ('3', '3', 1.0),
+          ('4', '4', 1.0);
+    "#,
+    );
+
+    let cases = vec![
+        (input, vec!["2", "3"]),
+        (
+            "number_field between [1, 3) or double_field = 2",
+            vec!["1", "2"],
+        ),
+        (
+            r#"date_field on_day "2026-10-17" or text_field contains ".""#,
+            vec!["3"],
+        ),
+        ("", vec!["1", "2", "3", "4"]),
+    ];
+
+    for (input, result) in cases {
+        assert_eq!(
+            result,
+            load_ids(&mut connection, text::parse(input).unwrap())
+        );
+    }
+
+    let error = |input: &str| text::parse::<Field>(input).err().unwrap();
+
+    assert_eq!(
+        error("number_field = 1 or numbr_field = 2"),
+        SyntaxError::new(
+            Some(20),
+            SyntaxErrorKind::UnknownField("numbr_field".to_string())
+        )
+    );
+    assert_eq!(
+        error("number_field like 1").to_string(),
+        "operator 'like' is not supported for field 'number_field' at position 0"
+    );
+    assert_eq!(
+        error(r#"bool_field = "yes""#).to_string(),
+        r#"invalid value String("yes") for field 'bool_field' at position 0"#
+    );
+    assert_eq!(
+        error("number_field.bike = 1"),
+        SyntaxError::new(
+            Some(0),
+            SyntaxErrorKind::ExpectedComparison("number_field".to_string())
+        )
+    );
+}
+
+#[test]
+fn query_string_test() {
+    use crate::syntax::{query_string, SyntaxError, SyntaxErrorKind, Value};
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, text_field, bool_field)
+        VALUES
+          ('1', 1, 'abc', true),
+          ('2', 2, 'abcd', true),
+          ('3', 3, 'xabc', false),
+          ('4', 4, 'xyz', true);
+
+        INSERT INTO join_to_test
+          (id, test_id, double_field)
+        VALUES
+          ('3', '3', 1.5);
+    "#,
+    );
+
+    let cases =
+        vec![
+        (
+            "?number_field[gte]=2&text_field[contains]=abc&or[0][bool_field]=true",
+            vec!["2"],
+        ),
+        (
+            "or[0][double_field][gt]=1&or[1][number_field][in][0]=1&or[1][number_field][in][1]=4",
+            vec!["1", "3", "4"],
+        ),
+        ("not[text_field][starts_with]=abc&double_field[is_null]=", vec!["4"]),
+    ];
+
+    for (query_string, result) in cases {
+        let conditions: Vec<Condition> = query_string::decode(query_string).unwrap();
+        assert_eq!(result, load_ids(&mut connection, conditions));
+    }
+
+    // Canonical, with values typed by field
+    let conditions: Vec<Condition> =
+        query_string::decode("number_field[gte]=2&double_field=1&bool_field=true").unwrap();
+    assert_eq!(
+        query_string::encode(&conditions),
+        "number_field[gte]=2&double_field[eq]=1.0&bool_field[eq]=true"
+    );
+
+    let error = |query_string: &str| query_string::decode::<Field>(query_string).err().unwrap();
+
+    assert_eq!(
+        error("number_field[gte]=2&numbr_field[eq]=1"),
+        SyntaxError::new(
+            Some(20),
+            SyntaxErrorKind::UnknownField("numbr_field".to_string())
+        )
+    );
+    assert_eq!(
+        error("number_field[gte]=2.5"),
+        SyntaxError::new(
+            Some(0),
+            SyntaxErrorKind::InvalidValue {
+                field: "number_field".to_string(),
+                value: Value::String("2.5".to_string())
+            }
+        )
+    );
+    assert_eq!(
+        error("bool_field[gt]=true").to_string(),
+        "operator 'gt' is not supported for field 'bool_field' at position 0"
+    );
+}
+
+#[test]
+fn odata_test() {
+    use crate::syntax::{odata, SyntaxError, SyntaxErrorKind};
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, text_field, bool_field, date_field)
+        VALUES
+          ('1', 1, 'abc', true, '2026-10-01'),
+          ('2', 2, 'abcd', true, NULL),
+          ('3', 3, 'xabc', false, '2026-10-18'),
+          ('4', 4, 'xyz', false, NULL);
+
+        INSERT INTO join_to_test
+          (id, test_id, double_field)
+        VALUES
+          ('3', '3', 1.5);
+    "#,
+    );
+
+    let cases = vec![
+        (
+            "number_field ge 2 and (contains(text_field, 'abc') or bool_field eq true) and double_field eq null",
+            vec!["2"],
+        ),
+        (
+            "not startswith(text_field, 'abc') and number_field in (3, 4)",
+            vec!["3", "4"],
+        ),
+        ("date_field lt 2026-10-18 or double_field gt 1", vec!["1", "3"]),
+        ("date_field ne null and endswith(text_field, 'bc')", vec!["1", "3"]),
+    ];
+
+    for (filter, result) in cases {
+        let conditions: Vec<Condition> = odata::parse(filter).unwrap();
+        assert_eq!(result, load_ids(&mut connection, conditions));
+    }
+
+    let error = |filter: &str| odata::parse::<Field>(filter).err().unwrap();
+
+    assert_eq!(
+        error("date_field ge 2026-10-18"),
+        SyntaxError::new(
+            Some(0),
+            SyntaxErrorKind::UnsupportedOperator {
+                field: "date_field".to_string(),
+                operator: crate::syntax::Operator::GreaterOrEqual
+            }
+        )
+    );
+    assert_eq!(
+        error("contains(bool_field, 'true')").to_string(),
+        "operator 'contains' is not supported for field 'bool_field' at position 9"
+    );
+}
+
+#[test]
+fn rsql_test() {
+    use crate::syntax::rsql;
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, text_field, bool_field)
+        VALUES
+          ('1', 1, 'cat', true),
+          ('2', 2, 'cow', false),
+          ('3', 3, 'dog', true),
+          ('4', 4, 'duck', false);
+
+        INSERT INTO join_to_test
+          (id, test_id, double_field)
+        VALUES
+          ('4', '4', 2.5);
+    "#,
+    );
+
+    let cases = vec![
+        (
+            "text_field==c*;bool_field==true,number_field=gt=3",
+            vec!["1", "4"],
+        ),
+        (
+            "text_field=out=(cat,dog);double_field=isnull=true",
+            vec!["2"],
+        ),
+        (
+            "double_field=ge=2.5 or (text_field!=*o*;number_field<2)",
+            vec!["1", "4"],
+        ),
+    ];
+
+    for (input, result) in cases {
+        let conditions: Vec<Condition> = rsql::parse(input).unwrap();
+        assert_eq!(result, load_ids(&mut connection, conditions));
+    }
+
+    // % and _ in wildcard values are literal
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, text_field)
+        VALUES
+          ('percent', 1, '50%'),
+          ('no_percent', 2, '500'),
+          ('underscore', 3, 'a_b'),
+          ('no_underscore', 4, 'axb');
+    "#,
+    );
+
+    let cases = vec![
+        ("text_field==50%*", vec!["percent"]),
+        ("text_field==*%", vec!["percent"]),
+        ("text_field==*_*", vec!["underscore"]),
+        ("text_field==a_*", vec!["underscore"]),
+        ("text_field==a*_b", vec!["underscore"]),
+        ("text_field==5*%", vec!["percent"]),
+        (
+            "text_field!=a*_b",
+            vec!["no_percent", "no_underscore", "percent"],
+        ),
+    ];
+
+    for (input, result) in cases {
+        let conditions: Vec<Condition> = rsql::parse(input).unwrap();
+        assert_eq!(result, load_ids(&mut connection, conditions));
+    }
+
+    // Wildcards are only for strings, values are typed by field
+    assert!(rsql::parse::<Field>("number_field==1*").is_err());
+    assert!(rsql::parse::<Field>("number_field=in=(1,a)").is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn mongo_test() {
+    use crate::syntax::mongo;
+    use serde_json::json;
+
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, text_field, bool_field, nullable_text_field)
+        VALUES
+          ('1', 1, 'Cat', true, 'a'),
+          ('2', 2, 'cow', false, NULL),
+          ('3', 3, 'dog', true, NULL),
+          ('4', 4, 'duck', false, 'b');
+    "#,
+    );
+
+    let cases = vec![
+        (
+            json!({"$and": [{"number_field": {"$gt": 1}}, {"$or": [{"bool_field": true}]}]}),
+            vec!["3"],
+        ),
+        (
+            json!({"number_field": {"$gte": 2, "$lt": 4}, "nullable_text_field": {"$exists": false}}),
+            vec!["2", "3"],
+        ),
+        (
+            json!({"$nor": [{"number_field": {"$in": [1, 2]}}, {"text_field": {"$regex": "^duck$"}}]}),
+            vec!["3"],
+        ),
+        // Negations match null, as missing fields do in MongoDB
+        (
+            json!({"nullable_text_field": {"$ne": "a"}}),
+            vec!["2", "3", "4"],
+        ),
+        (
+            json!({"nullable_text_field": {"$nin": ["b"]}, "number_field": {"$not": {"$lt": 2}}}),
+            vec!["2", "3"],
+        ),
+        (
+            json!({"$nor": [{"nullable_text_field": "a"}, {"bool_field": false}]}),
+            vec!["3"],
+        ),
+        (
+            json!({"text_field": {"$not": {"$regex": "c", "$options": "i"}}}),
+            vec!["3"],
+        ),
+    ];
+
+    for (query, result) in cases {
+        let conditions: Vec<Condition> = mongo::decode(&query).unwrap();
+        assert_eq!(result, load_ids(&mut connection, conditions));
+    }
+
+    // Values are typed by field
+    assert!(mongo::decode::<Field>(&json!({"number_field": {"$gt": 1.5}})).is_err());
+    assert!(mongo::decode::<Field>(&json!({"bool_field": "yes"})).is_err());
+    assert_eq!(
+        mongo::decode::<Field>(&json!({"$or": [{"number_field": 1}, {"bool_field": "yes"}]}))
+            .unwrap_err()
+            .to_string(),
+        r#"invalid value String("yes") for field 'bool_field' at '/$or/1/bool_field'"#
+    );
+}
+
+// Loads rows of test left joined to join_to_test, in the same order as filtered queries
+#[cfg(test)]
+fn load_test_rows(connection: &mut SqliteConnection) -> Vec<TestRow> {
+    test::dsl::test
+        .left_join(join_to_test::dsl::join_to_test)
+        .select((
+            test::dsl::id,
+            test::dsl::number_field,
+            test::dsl::text_field,
+            test::dsl::bool_field,
+            test::dsl::date_field,
+            test::dsl::time_field,
+            test::dsl::timestamp_field,
+            test::dsl::nullable_number_field,
+            test::dsl::nullable_text_field,
+            test::dsl::nullable_bool_field,
+            join_to_test::dsl::double_field.nullable(),
+        ))
+        .order_by((test::dsl::id, join_to_test::dsl::id))
+        .load(connection)
+        .unwrap()
+}
+
+// Ids of rows matched by conditions in SQLite and in memory (or the same error for both)
+#[cfg(test)]
+fn queried_and_evaluated(
+    connection: &mut SqliteConnection,
+    rows: &[TestRow],
+    conditions: Vec<Condition>,
+    options: &FilterOptions,
+) -> (
+    Result<Vec<String>, FilterError>,
+    Result<Vec<String>, FilterError>,
+) {
+    let evaluated = rows
+        .iter()
+        .filter_map(
+            |row| match evaluate::evaluate_group(&conditions, AndOr::And, row, options) {
+                Ok(Some(true)) => Some(Ok(row.id.clone())),
+                Ok(_) => None,
+                Err(error) => Some(Err(error)),
+            },
+        )
+        .collect();
+
+    (load_ids_with(connection, conditions, options), evaluated)
+}
+
+// Random conditions and values (close to each other and to the rows, for conditions to match)
+#[cfg(test)]
+mod strategy {
+    use super::*;
+    use proptest::{collection::vec, prelude::*};
+
+    // Sunday
+    pub(super) fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 18)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    pub(super) fn number() -> BoxedStrategy<i32> {
+        prop_oneof![-1..4, Just(i32::MAX)].boxed()
+    }
+
+    pub(super) fn double() -> BoxedStrategy<f64> {
+        prop_oneof![Just(0.5), Just(1.0), Just(2.5), Just(-0.0)].boxed()
+    }
+
+    // Including LIKE wildcards, escape character and non ASCII
+    pub(super) fn text() -> BoxedStrategy<String> {
+        "[abcAB%_\\\\ä]{0,3}".boxed()
+    }
+
+    fn wildcard() -> BoxedStrategy<String> {
+        "[abA%_*]{0,3}".boxed()
+    }
+
+    pub(super) fn date() -> BoxedStrategy<NaiveDate> {
+        (0u64..24)
+            .prop_map(|days| NaiveDate::from_ymd_opt(2026, 9, 27).unwrap() + Days::new(days))
+            .boxed()
+    }
+
+    pub(super) fn time() -> BoxedStrategy<NaiveTime> {
+        (0u32..24, prop_oneof![Just(0u32), Just(30), Just(59)])
+            .prop_map(|(hour, minute)| NaiveTime::from_hms_opt(hour, minute, 0).unwrap())
+            .boxed()
+    }
+
+    pub(super) fn timestamp() -> BoxedStrategy<NaiveDateTime> {
+        (date(), time())
+            .prop_map(|(date, time)| date.and_time(time))
+            .boxed()
+    }
+
+    fn number_filter<T: std::fmt::Debug + Clone + 'static>(
+        value: BoxedStrategy<T>,
+    ) -> BoxedStrategy<NumberFilter<T>> {
+        prop_oneof![
+            value.clone().prop_map(NumberFilter::Equal),
+            value.clone().prop_map(NumberFilter::NotEqual),
+            value.clone().prop_map(NumberFilter::GreaterThen),
+            value.clone().prop_map(NumberFilter::LowerThen),
+            value.clone().prop_map(NumberFilter::GreaterOrEqual),
+            value.clone().prop_map(NumberFilter::LowerOrEqual),
+            (value.clone(), value.clone(), any::<bool>(), any::<bool>()).prop_map(
+                |(from, to, from_inclusive, to_inclusive)| NumberFilter::Between {
+                    from,
+                    to,
+                    from_inclusive,
+                    to_inclusive,
+                }
+            ),
+            vec(value.clone(), 0..3).prop_map(NumberFilter::In),
+            vec(value, 0..3).prop_map(NumberFilter::NotIn),
+            any::<bool>().prop_map(|is_null| match is_null {
+                true => NumberFilter::IsNull,
+                false => NumberFilter::IsNotNull,
+            }),
+        ]
+        .boxed()
+    }
+
+    fn string_filter() -> BoxedStrategy<StringFilter> {
+        prop_oneof![
+            text().prop_map(StringFilter::Equal),
+            text().prop_map(StringFilter::NotEqual),
+            text().prop_map(StringFilter::Like),
+            text().prop_map(StringFilter::NotLike),
+            vec(text(), 0..3).prop_map(StringFilter::In),
+            vec(text(), 0..3).prop_map(StringFilter::NotIn),
+            text().prop_map(StringFilter::StartsWith),
+            text().prop_map(StringFilter::EndsWith),
+            text().prop_map(StringFilter::Contains),
+            wildcard().prop_map(StringFilter::Wildcard),
+            wildcard().prop_map(StringFilter::NotWildcard),
+            text().prop_map(StringFilter::EqualIgnoreCase),
+            text().prop_map(StringFilter::ContainsIgnoreCase),
+            vec(text(), 0..3).prop_map(StringFilter::InIgnoreCase),
+            any::<bool>().prop_map(|is_null| match is_null {
+                true => StringFilter::IsNull,
+                false => StringFilter::IsNotNull,
+            }),
+        ]
+        .boxed()
+    }
+
+    fn boolean_filter() -> BoxedStrategy<BooleanFilter> {
+        prop_oneof![
+            Just(()).prop_map(|_| BooleanFilter::True),
+            Just(()).prop_map(|_| BooleanFilter::False),
+            Just(()).prop_map(|_| BooleanFilter::IsNull),
+            Just(()).prop_map(|_| BooleanFilter::IsNotNull),
+        ]
+        .boxed()
+    }
+
+    fn date_time_filter<T: std::fmt::Debug + Clone + 'static>(
+        value: BoxedStrategy<T>,
+    ) -> BoxedStrategy<DateTimeFilter<T>> {
+        let duration = (
+            0u32..48,
+            prop_oneof![
+                Just(TimeUnit::Minutes),
+                Just(TimeUnit::Hours),
+                Just(TimeUnit::Days),
+                Just(TimeUnit::Weeks),
+            ],
+        );
+        prop_oneof![
+            value.clone().prop_map(DateTimeFilter::Equal),
+            value.clone().prop_map(DateTimeFilter::Before),
+            value.clone().prop_map(DateTimeFilter::After),
+            (value.clone(), value).prop_map(|(from, to)| DateTimeFilter::Between { from, to }),
+            date().prop_map(DateTimeFilter::OnDay),
+            duration
+                .clone()
+                .prop_map(|(number, unit)| DateTimeFilter::Last(number, unit)),
+            duration.prop_map(|(number, unit)| DateTimeFilter::OlderThan(number, unit)),
+            prop_oneof![
+                Just(Period::Day),
+                Just(Period::Week),
+                Just(Period::Month),
+                Just(Period::Year),
+            ]
+            .prop_map(DateTimeFilter::This),
+            Just(DateTimeFilter::IsNull),
+            Just(DateTimeFilter::IsNotNull),
+        ]
+        .boxed()
+    }
+
+    // Nested And/Or/Not groups (including empty ones), shrinking to a single field condition
+    pub(super) fn condition() -> BoxedStrategy<Condition> {
+        let field = prop_oneof![
+            number_filter(number()).prop_map(Condition::number_field),
+            number_filter(double()).prop_map(Condition::double_field),
+            string_filter().prop_map(Condition::text_field),
+            boolean_filter().prop_map(Condition::bool_field),
+            date_time_filter(date()).prop_map(Condition::date_field),
+            date_time_filter(time()).prop_map(Condition::time_field),
+            date_time_filter(timestamp()).prop_map(Condition::timestamp_field),
+            number_filter(number()).prop_map(Condition::nullable_number_field),
+            string_filter().prop_map(Condition::nullable_text_field),
+            boolean_filter().prop_map(Condition::nullable_bool_field),
+            any::<bool>().prop_map(|value| match value {
+                true => Condition::True,
+                false => Condition::False,
+            }),
+        ];
+        field
+            .prop_recursive(4, 32, 4, |inner| {
+                prop_oneof![
+                    vec(inner.clone(), 0..4).prop_map(Condition::And),
+                    vec(inner.clone(), 0..4).prop_map(Condition::Or),
+                    inner.prop_map(|condition| Condition::Not(Box::new(condition))),
+                ]
+            })
+            .boxed()
+    }
+
+    // Row of test with it's join_to_test rows
+    #[derive(Debug)]
+    pub(super) struct NewRow {
+        number_field: i32,
+        text_field: String,
+        bool_field: bool,
+        date_field: Option<NaiveDate>,
+        time_field: Option<NaiveTime>,
+        timestamp_field: Option<NaiveDateTime>,
+        nullable_number_field: Option<i32>,
+        nullable_text_field: Option<String>,
+        nullable_bool_field: Option<bool>,
+        double_fields: Vec<f64>,
+    }
+
+    impl NewRow {
+        pub(super) fn insert(&self, id: &str, connection: &mut SqliteConnection) {
+            diesel::insert_into(test::table)
+                .values((
+                    test::dsl::id.eq(id),
+                    test::dsl::number_field.eq(self.number_field),
+                    test::dsl::text_field.eq(&self.text_field),
+                    test::dsl::bool_field.eq(self.bool_field),
+                    test::dsl::date_field.eq(self.date_field),
+                    test::dsl::time_field.eq(self.time_field),
+                    test::dsl::timestamp_field.eq(self.timestamp_field),
+                    test::dsl::nullable_number_field.eq(self.nullable_number_field),
+                    test::dsl::nullable_text_field.eq(&self.nullable_text_field),
+                    test::dsl::nullable_bool_field.eq(self.nullable_bool_field),
+                ))
+                .execute(connection)
+                .unwrap();
+
+            for (index, double_field) in self.double_fields.iter().enumerate() {
+                diesel::insert_into(join_to_test::table)
+                    .values((
+                        join_to_test::dsl::id.eq(format!("{id}.{index}")),
+                        join_to_test::dsl::test_id.eq(id),
+                        join_to_test::dsl::double_field.eq(double_field),
+                    ))
+                    .execute(connection)
+                    .unwrap();
+            }
+        }
+    }
+
+    // At least one row, for errors of the conditions to also be returned by evaluation
+    pub(super) fn rows() -> BoxedStrategy<Vec<NewRow>> {
+        let row = (
+            number(),
+            text(),
+            any::<bool>(),
+            proptest::option::of(date()),
+            proptest::option::of(time()),
+            proptest::option::of(timestamp()),
+            proptest::option::of(number()),
+            proptest::option::of(text()),
+            proptest::option::of(any::<bool>()),
+            vec(double(), 0..3),
+        )
+            .prop_map(
+                |(
+                    number_field,
+                    text_field,
+                    bool_field,
+                    date_field,
+                    time_field,
+                    timestamp_field,
+                    nullable_number_field,
+                    nullable_text_field,
+                    nullable_bool_field,
+                    double_fields,
+                )| NewRow {
+                    number_field,
+                    text_field,
+                    bool_field,
+                    date_field,
+                    time_field,
+                    timestamp_field,
+                    nullable_number_field,
+                    nullable_text_field,
+                    nullable_bool_field,
+                    double_fields,
+                },
+            );
+        vec(row, 1..6).boxed()
+    }
+
+    pub(super) fn options() -> BoxedStrategy<FilterOptions> {
+        prop_oneof![Just(EmptyGroup::Identity), Just(EmptyGroup::Reject)]
+            .prop_map(|empty_group| FilterOptions {
+                empty_group,
+                clock: now,
+                now: None,
+            })
+            .boxed()
+    }
+}
+
+// Random conditions are evaluated in memory and by SQLite, for the same rows
+#[test]
+fn evaluate_test() {
+    use proptest::{prop_assert_eq, test_runner::TestRunner};
+
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, text_field, bool_field, date_field, time_field, timestamp_field,
+           nullable_number_field, nullable_text_field, nullable_bool_field)
+        VALUES
+          ('1', 1, 'abc', true, '2026-10-18', '09:30:00', '2026-10-18 09:30:00', 1, 'Abc', true),
+          ('2', 2, 'a%c', false, '2026-10-12', '11:00:00', '2026-10-12 11:00:00', NULL, NULL, NULL),
+          ('3', 3, 'A_b\c', true, '2026-09-30', '23:59:59', '2026-09-30 23:59:59', 3, 'ä', false),
+          ('4', -1, '', false, NULL, NULL, NULL, 2, 'b', NULL);
+
+        INSERT INTO join_to_test
+          (id, test_id, double_field)
+        VALUES
+          ('j1', '1', 0.5),
+          ('j2', '1', 2.5),
+          ('j3', '3', 1.0);
+    "#,
+    );
+
+    let rows = load_test_rows(&mut connection);
+
+    // Runner closure is Fn
+    let connection = std::cell::RefCell::new(connection);
+    TestRunner::default()
+        .run(
+            &(strategy::condition(), strategy::options()),
+            |(condition, options)| {
+                let (queried, evaluated) = queried_and_evaluated(
+                    &mut connection.borrow_mut(),
+                    &rows,
+                    vec![condition],
+                    &options,
+                );
+                prop_assert_eq!(queried, evaluated);
+                Ok(())
+            },
+        )
+        .unwrap();
+}
+
+// Random conditions (top level And of nested groups) over random table contents, in a new
+// database for every case, compared to in memory evaluation, failures are shrunk to minimal
+// conditions and rows
+#[test]
+fn random_test() {
+    use proptest::{
+        collection::vec,
+        prop_assert_eq,
+        test_runner::{Config, TestRunner},
+    };
+
+    let config = Config {
+        cases: 512,
+        ..Config::default()
+    };
+    TestRunner::new(config)
+        .run(
+            &(
+                vec(strategy::condition(), 0..4),
+                strategy::rows(),
+                strategy::options(),
+            ),
+            |(conditions, rows, options)| {
+                let mut connection = connection_with_rows("");
+                for (index, row) in rows.iter().enumerate() {
+                    row.insert(&index.to_string(), &mut connection);
+                }
+
+                let rows = load_test_rows(&mut connection);
+                let (queried, evaluated) =
+                    queried_and_evaluated(&mut connection, &rows, conditions, &options);
+                prop_assert_eq!(queried, evaluated);
+                Ok(())
+            },
+        )
+        .unwrap();
+}
+
+// Hand written three valued truth tables, independent of the in memory evaluation, against
+// SQLite and against evaluate, T is true, F is false and N is NULL (unknown)
+#[test]
+fn truth_table_test() {
+    let mut connection = connection_with_rows(
+        r#"
+        INSERT INTO test
+          (id, number_field, nullable_number_field, nullable_bool_field)
+        VALUES
+          ('1', 0, 1, true),
+          ('2', 0, 1, false),
+          ('3', 0, 1, NULL),
+          ('4', 0, 2, true),
+          ('5', 0, 2, false),
+          ('6', 0, 2, NULL),
+          ('7', 0, NULL, true),
+          ('8', 0, NULL, false),
+          ('9', 0, NULL, NULL);
+    "#,
+    );
+    let rows = load_test_rows(&mut connection);
+
+    // Rows are every combination of p and q, p major
+    let p = || Condition::nullable_number_field(NumberFilter::Equal(1));
+    let q = || Condition::nullable_bool_field(BooleanFilter::True);
+    let not = |condition: Condition| Condition::Not(Box::new(condition));
+    let cases = vec![
+        (p(), "TTTFFFNNN"),
+        (q(), "TFNTFNTFN"),
+        (not(p()), "FFFTTTNNN"),
+        (not(not(p())), "TTTFFFNNN"),
+        (Condition::And(vec![p(), q()]), "TFNFFFNFN"),
+        (Condition::Or(vec![p(), q()]), "TTTTFNTNN"),
+        (not(Condition::And(vec![p(), q()])), "FTNTTTNTN"),
+        (not(Condition::Or(vec![p(), q()])), "FFFFTNFNN"),
+        (Condition::And(vec![not(p()), q()]), "FFFTFNNFN"),
+        (Condition::Or(vec![p(), not(p())]), "TTTTTTNNN"),
+        (Condition::And(vec![q(), Condition::False]), "FFFFFFFFF"),
+        (Condition::Or(vec![q(), Condition::True]), "TTTTTTTTT"),
+        (not(Condition::True), "FFFFFFFFF"),
+        (
+            Condition::nullable_number_field(NumberFilter::IsNull),
+            "FFFFFFTTT",
+        ),
+        (
+            not(Condition::nullable_number_field(NumberFilter::IsNull)),
+            "TTTTTTFFF",
+        ),
+        (
+            Condition::nullable_number_field(NumberFilter::NotIn(vec![1])),
+            "FFFTTTNNN",
+        ),
+        (
+            Condition::nullable_number_field(NumberFilter::In(vec![])),
+            "FFFFFFFFF",
+        ),
+        (
+            not(Condition::nullable_bool_field(BooleanFilter::False)),
+            "TFNTFNTFN",
+        ),
+    ];
+
+    for (condition, table) in cases {
+        let expected: Vec<Option<bool>> = table
+            .chars()
+            .map(|value| match value {
+                'T' => Some(true),
+                'F' => Some(false),
+                _ => None,
+            })
+            .collect();
+
+        let matched = load_ids(&mut connection, vec![condition.clone()]);
+        let not_matched = load_ids(&mut connection, vec![not(condition.clone())]);
+        let queried: Vec<Option<bool>> = rows
+            .iter()
+            .map(
+                |row| match (matched.contains(&row.id), not_matched.contains(&row.id)) {
+                    (true, false) => Some(true),
+                    (false, true) => Some(false),
+                    (false, false) => None,
+                    (true, true) => panic!("{:?} and its negation match {}", condition, row.id),
+                },
+            )
+            .collect();
+        assert_eq!(expected, queried, "{:?}", condition);
+
+        let evaluated: Vec<Option<bool>> = rows
+            .iter()
+            .map(|row| condition.evaluate(row).unwrap())
+            .collect();
+        assert_eq!(expected, evaluated, "{:?}", condition);
+    }
+}
@@ -0,0 +1,321 @@
+/*
+    In memory evaluation of conditions against loaded rows (caches, filtering pushed rows), with the
+    same three valued logic as SQL produced by to_boxed_condition, None is NULL (unknown) and rows
+    are matched when condition is Some(true)
+*/
+
+use std::ops::RangeBounds;
+
+use super::*;
+
+// Implemented by table's field enum for the row struct it's evaluated against
+pub(crate) trait EvaluateCondition {
+    type Row;
+
+    fn evaluate_with(
+        &self,
+        row: &Self::Row,
+        options: &FilterOptions,
+    ) -> Result<Option<bool>, FilterError>;
+}
+
+impl<F: EvaluateCondition> ConditionTree<F> {
+    pub(crate) fn evaluate(&self, row: &F::Row) -> Result<Option<bool>, FilterError> {
+        self.evaluate_with(row, &FilterOptions::default())
+    }
+}
+
+impl<F: EvaluateCondition> EvaluateCondition for ConditionTree<F> {
+    type Row = F::Row;
+
+    fn evaluate_with(
+        &self,
+        row: &Self::Row,
+        options: &FilterOptions,
+    ) -> Result<Option<bool>, FilterError> {
+        Ok(match self {
+            ConditionTree::Field(field) => field.evaluate_with(row, options)?,
+            ConditionTree::And(conditions) => evaluate_group(conditions, AndOr::And, row, options)?,
+            ConditionTree::Or(conditions) => evaluate_group(conditions, AndOr::Or, row, options)?,
+            ConditionTree::Not(condition) => {
+                condition.evaluate_with(row, options)?.map(|value| !value)
+            }
+            ConditionTree::True => Some(true),
+            ConditionTree::False => Some(false),
+        })
+    }
+}
+
+// Same as create_filter, every condition is evaluated (errors are not short circuited)
+pub(crate) fn evaluate_group<C: EvaluateCondition>(
+    conditions: &[C],
+    and_or: AndOr,
+    row: &C::Row,
+    options: &FilterOptions,
+) -> Result<Option<bool>, FilterError> {
+    if conditions.is_empty() {
+        return match (options.empty_group, and_or) {
+            (EmptyGroup::Reject, _) => Err(FilterError::EmptyGroup),
+            (EmptyGroup::Identity, AndOr::And) => Ok(Some(true)),
+            (EmptyGroup::Identity, AndOr::Or) => Ok(Some(false)),
+        };
+    }
+
+    conditions.iter().try_fold(
+        match and_or {
+            AndOr::And => Some(true),
+            AndOr::Or => Some(false),
+        },
+        |result, condition| {
+            let value = condition.evaluate_with(row, options)?;
+            Ok(match and_or {
+                AndOr::And => and(result, value),
+                AndOr::Or => or(result, value),
+            })
+        },
+    )
+}
+
+fn and(left: Option<bool>, right: Option<bool>) -> Option<bool> {
+    match (left, right) {
+        (Some(false), _) | (_, Some(false)) => Some(false),
+        (Some(true), Some(true)) => Some(true),
+        _ => None,
+    }
+}
+
+fn or(left: Option<bool>, right: Option<bool>) -> Option<bool> {
+    match (left, right) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    }
+}
+
+// SQLite LIKE, % is any sequence and _ is any character, case insensitive for ASCII only
+fn like(value: &str, pattern: &str, escape: Option<char>) -> bool {
+    let value: Vec<char> = value.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pattern = pattern.chars();
+    while let Some(character) = pattern.next() {
+        tokens.push(match character {
+            character if Some(character) == escape => match pattern.next() {
+                Some(escaped) => Like::Char(escaped),
+                None => return false,
+            },
+            '%' => Like::Any,
+            '_' => Like::One,
+            character => Like::Char(character),
+        });
+    }
+
+    // Greedy, on a mismatch the last % takes one more character, earlier % never have to (as what
+    // follows them up to the last % matched already), so this is O(value * pattern)
+    let (mut current, mut token) = (0, 0);
+    let mut last_any: Option<(usize, usize)> = None;
+    while current < value.len() {
+        match tokens.get(token) {
+            Some(Like::Any) => {
+                token += 1;
+                last_any = Some((token, current));
+            }
+            Some(Like::One) => {
+                token += 1;
+                current += 1;
+            }
+            Some(Like::Char(character)) if value[current].eq_ignore_ascii_case(character) => {
+                token += 1;
+                current += 1;
+            }
+            _ => match last_any {
+                Some((after_any, skipped)) => {
+                    token = after_any;
+                    current = skipped + 1;
+                    last_any = Some((after_any, current));
+                }
+                None => return false,
+            },
+        }
+    }
+    tokens[token..]
+        .iter()
+        .all(|token| matches!(token, Like::Any))
+}
+
+enum Like {
+    Any,
+    One,
+    Char(char),
+}
+
+// lower() in SQLite (without ICU) only lowers ASCII characters
+fn lower(value: &str) -> String {
+    value.to_ascii_lowercase()
+}
+
+impl<T: PartialOrd> NumberFilter<T> {
+    pub(crate) fn evaluate(&self, value: Option<&T>) -> Option<bool> {
+        match self {
+            NumberFilter::Equal(other) => value.map(|value| value == other),
+            NumberFilter::NotEqual(other) => value.map(|value| value != other),
+            NumberFilter::GreaterThen(other) => value.map(|value| value > other),
+            NumberFilter::LowerThen(other) => value.map(|value| value < other),
+            NumberFilter::GreaterOrEqual(other) => value.map(|value| value >= other),
+            NumberFilter::LowerOrEqual(other) => value.map(|value| value <= other),
+            NumberFilter::Between {
+                from,
+                to,
+                from_inclusive,
+                to_inclusive,
+            } => value.map(|value| {
+                let after_from = if *from_inclusive {
+                    value >= from
+                } else {
+                    value > from
+                };
+                let before_to = if *to_inclusive {
+                    value <= to
+                } else {
+                    value < to
+                };
+                after_from && before_to
+            }),
+            // Empty lists are 1=0 and 1=1 in diesel, even for NULL
+            NumberFilter::In(values) if values.is_empty() => Some(false),
+            NumberFilter::NotIn(values) if values.is_empty() => Some(true),
+            NumberFilter::In(values) => value.map(|value| values.contains(value)),
+            NumberFilter::NotIn(values) => value.map(|value| !values.contains(value)),
+            NumberFilter::IsNull => Some(value.is_none()),
+            NumberFilter::IsNotNull => Some(value.is_some()),
+        }
+    }
+}
+
+impl StringFilter {
+    pub(crate) fn evaluate(&self, value: Option<&str>) -> Option<bool> {
+        let escaped_like =
+            |pattern: String| value.map(|value| like(value, &pattern, Some(LIKE_ESCAPE)));
+
+        match self {
+            StringFilter::Equal(other) => value.map(|value| value == other),
+            StringFilter::NotEqual(other) => value.map(|value| value != other),
+            StringFilter::Like(pattern) => value.map(|value| like(value, pattern, None)),
+            StringFilter::NotLike(pattern) => value.map(|value| !like(value, pattern, None)),
+            StringFilter::In(values) if values.is_empty() => Some(false),
+            StringFilter::NotIn(values) if values.is_empty() => Some(true),
+            StringFilter::In(values) => {
+                value.map(|value| values.iter().any(|other| value == other))
+            }
+            StringFilter::NotIn(values) => {
+                value.map(|value| values.iter().all(|other| value != other))
+            }
+            StringFilter::StartsWith(other) => escaped_like(format!("{}%", escape_like(other))),
+            StringFilter::EndsWith(other) => escaped_like(format!("%{}", escape_like(other))),
+            StringFilter::Contains(other) => escaped_like(format!("%{}%", escape_like(other))),
+            StringFilter::Wildcard(other) => escaped_like(wildcard_like(other)),
+            StringFilter::NotWildcard(other) => {
+                escaped_like(wildcard_like(other)).map(|matches| !matches)
+            }
+            StringFilter::EqualIgnoreCase(other) => value.map(|value| lower(value) == lower(other)),
+            StringFilter::ContainsIgnoreCase(other) => {
+                let pattern = lower(&format!("%{}%", escape_like(other)));
+                value.map(|value| like(&lower(value), &pattern, Some(LIKE_ESCAPE)))
+            }
+            // Or of comparisons, constant FALSE when empty
+            StringFilter::InIgnoreCase(values) if values.is_empty() => Some(false),
+            StringFilter::InIgnoreCase(values) => {
+                value.map(|value| values.iter().any(|other| lower(value) == lower(other)))
+            }
+            StringFilter::IsNull => Some(value.is_none()),
+            StringFilter::IsNotNull => Some(value.is_some()),
+        }
+    }
+}
+
+impl BooleanFilter {
+    pub(crate) fn evaluate(&self, value: Option<bool>) -> Option<bool> {
+        match self {
+            BooleanFilter::True => value,
+            BooleanFilter::False => value.map(|value| !value),
+            BooleanFilter::IsNull => Some(value.is_none()),
+            BooleanFilter::IsNotNull => Some(value.is_some()),
+        }
+    }
+}
+
+impl<T: Temporal + PartialOrd + Clone> DateTimeFilter<T> {
+    // Relative and calendar filters are resolved with options clock, as when boxing
+    pub(crate) fn evaluate(
+        &self,
+        value: Option<&T>,
+        options: &FilterOptions,
+    ) -> Result<Option<bool>, FilterError> {
+        Ok(match self.clone().resolve(options.now())? {
+            ResolvedDateTimeFilter::Equal(other) => value.map(|value| *value == other),
+            ResolvedDateTimeFilter::IsNull => Some(value.is_none()),
+            ResolvedDateTimeFilter::IsNotNull => Some(value.is_some()),
+            // Constant TRUE
+            ResolvedDateTimeFilter::Range(Bound::Unbounded, Bound::Unbounded) => Some(true),
+            ResolvedDateTimeFilter::Range(from, to) => {
+                value.map(|value| (from.as_ref(), to.as_ref()).contains(value))
+            }
+        })
+    }
+}
+
+#[test]
+fn test() {
+    assert!(like("Abc", "a%", None));
+    assert!(like("a%c", "a\\%c", Some('\\')));
+    assert!(!like("abc", "a\\%c", Some('\\')));
+    assert!(like("abc", "a_c", None));
+    assert!(!like("ac", "a_c", None));
+    assert!(like("", "%%", None));
+    // Only ASCII is case insensitive
+    assert!(!like("Ä", "ä", None));
+    assert!(like("abcbd", "%b_%d", None));
+    assert!(!like("abcbe", "%b%d", None));
+    assert!(like("a%b", "%\\%%", Some('\\')));
+    assert!(!like("ab", "ab\\", Some('\\')));
+    // Many % on a long value don't backtrack exponentially
+    let value = "a".repeat(10_000);
+    assert!(!like(&value, &format!("{}b", "%a".repeat(50)), None));
+    assert!(like(&value, &format!("{}%", "%a".repeat(50)), None));
+
+    assert_eq!(NumberFilter::Equal(1).evaluate(None), None);
+    assert_eq!(
+        NumberFilter::In(Vec::new()).evaluate(None::<&i32>),
+        Some(false)
+    );
+    assert_eq!(
+        NumberFilter::NotIn(vec![1, 2]).evaluate(Some(&3)),
+        Some(true)
+    );
+    assert_eq!(
+        StringFilter::Contains("%".to_string()).evaluate(Some("a%b")),
+        Some(true)
+    );
+    assert_eq!(
+        StringFilter::Contains("%".to_string()).evaluate(Some("ab")),
+        Some(false)
+    );
+    assert_eq!(
+        StringFilter::Wildcard("a*%".to_string()).evaluate(Some("ab%")),
+        Some(true)
+    );
+    assert_eq!(
+        StringFilter::Wildcard("a*%".to_string()).evaluate(Some("ab")),
+        Some(false)
+    );
+    assert_eq!(
+        StringFilter::InIgnoreCase(vec!["ABC".to_string()]).evaluate(Some("abc")),
+        Some(true)
+    );
+    assert_eq!(BooleanFilter::False.evaluate(None), None);
+    assert_eq!(BooleanFilter::IsNull.evaluate(None), Some(true));
+
+    assert_eq!(and(None, Some(false)), Some(false));
+    assert_eq!(and(None, Some(true)), None);
+    assert_eq!(or(None, Some(true)), Some(true));
+    assert_eq!(or(None, Some(false)), None);
+}
@@ -0,0 +1,245 @@
+/*
+    GraphQL inputs, filters are oneOf inputs (exactly one operator), i.e. {gte: 2}, and conditions are
+    objects with and, or, not and field keys (see dynamic_filter! macro), i.e.
+    {numberField: {gte: 2}, or: [{textField: {like: "4%"}}, {boolField: {eq: true}}]}
+*/
+
+use async_graphql::{InputObject, InputType, OneofObject};
+
+use crate::*;
+
+// Filter type to it's GraphQL input
+pub(crate) trait GraphqlFilter: Sized {
+    type Input: InputType;
+
+    fn from_input(input: Self::Input) -> Self;
+}
+
+// Implemented by Field enum of every table (see dynamic_filter! macro)
+pub(crate) trait GraphqlFields: Sized {
+    type Input: InputType;
+    type CountInput: InputType;
+}
+
+// Inner statement conditions
+impl<F> GraphqlFilter for Vec<ConditionTree<F>>
+where
+    F: GraphqlFields,
+    ConditionTree<F>: From<F::Input>,
+{
+    type Input = F::Input;
+
+    fn from_input(input: Self::Input) -> Self {
+        vec![input.into()]
+    }
+}
+
+impl<F> GraphqlFilter for RelationCount<ConditionTree<F>>
+where
+    F: GraphqlFields,
+    RelationCount<ConditionTree<F>>: From<F::CountInput>,
+{
+    type Input = F::CountInput;
+
+    fn from_input(input: Self::Input) -> Self {
+        input.into()
+    }
+}
+
+// diesel_rs_dynamic_filters::inner_statement::bike_trip -> BikeTripConditionInput
+pub(crate) fn type_name(module_path: &str, suffix: &str) -> String {
+    let module = module_path.rsplit("::").next().unwrap_or(module_path);
+    let mut name: String = module
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect();
+    name.push_str(suffix);
+    name
+}
+
+// Number filter and range inputs for a number type
+macro_rules! number_filter_input {
+    ($input:ident, $range_input:ident, $type:ty) => {
+        #[derive(InputObject)]
+        pub(crate) struct $range_input {
+            from: $type,
+            to: $type,
+            #[graphql(default = true)]
+            from_inclusive: bool,
+            #[graphql(default = true)]
+            to_inclusive: bool,
+        }
+
+        #[derive(OneofObject)]
+        pub(crate) enum $input {
+            Eq($type),
+            Ne($type),
+            Gt($type),
+            Lt($type),
+            Gte($type),
+            Lte($type),
+            Between($range_input),
+            In(Vec<$type>),
+            NotIn(Vec<$type>),
+            // true for is null, false for is not null
+            IsNull(bool),
+        }
+
+        impl GraphqlFilter for NumberFilter<$type> {
+            type Input = $input;
+
+            fn from_input(input: Self::Input) -> Self {
+                match input {
+                    $input::Eq(value) => NumberFilter::Equal(value),
+                    $input::Ne(value) => NumberFilter::NotEqual(value),
+                    $input::Gt(value) => NumberFilter::GreaterThen(value),
+                    $input::Lt(value) => NumberFilter::LowerThen(value),
+                    $input::Gte(value) => NumberFilter::GreaterOrEqual(value),
+                    $input::Lte(value) => NumberFilter::LowerOrEqual(value),
+                    $input::Between(range) => NumberFilter::Between {
+                        from: range.from,
+                        to: range.to,
+                        from_inclusive: range.from_inclusive,
+                        to_inclusive: range.to_inclusive,
+                    },
+                    $input::In(values) => NumberFilter::In(values),
+                    $input::NotIn(values) => NumberFilter::NotIn(values),
+                    $input::IsNull(true) => NumberFilter::IsNull,
+                    $input::IsNull(false) => NumberFilter::IsNotNull,
+                }
+            }
+        }
+    };
+}
+
+number_filter_input!(IntFilterInput, IntRangeInput, i32);
+number_filter_input!(FloatFilterInput, FloatRangeInput, f64);
+number_filter_input!(BigIntFilterInput, BigIntRangeInput, i64);
+
+#[derive(OneofObject)]
+pub(crate) enum StringFilterInput {
+    Eq(String),
+    Ne(String),
+    Like(String),
+    NotLike(String),
+    In(Vec<String>),
+    NotIn(Vec<String>),
+    StartsWith(String),
+    EndsWith(String),
+    Contains(String),
+    Wildcard(String),
+    NotWildcard(String),
+    EqIgnoreCase(String),
+    ContainsIgnoreCase(String),
+    InIgnoreCase(Vec<String>),
+    IsNull(bool),
+}
+
+impl GraphqlFilter for StringFilter {
+    type Input = StringFilterInput;
+
+    fn from_input(input: Self::Input) -> Self {
+        match input {
+            StringFilterInput::Eq(value) => StringFilter::Equal(value),
+            StringFilterInput::Ne(value) => StringFilter::NotEqual(value),
+            StringFilterInput::Like(value) => StringFilter::Like(value),
+            StringFilterInput::NotLike(value) => StringFilter::NotLike(value),
+            StringFilterInput::In(values) => StringFilter::In(values),
+            StringFilterInput::NotIn(values) => StringFilter::NotIn(values),
+            StringFilterInput::StartsWith(value) => StringFilter::StartsWith(value),
+            StringFilterInput::EndsWith(value) => StringFilter::EndsWith(value),
+            StringFilterInput::Contains(value) => StringFilter::Contains(value),
+            StringFilterInput::Wildcard(value) => StringFilter::Wildcard(value),
+            StringFilterInput::NotWildcard(value) => StringFilter::NotWildcard(value),
+            StringFilterInput::EqIgnoreCase(value) => StringFilter::EqualIgnoreCase(value),
+            StringFilterInput::ContainsIgnoreCase(value) => StringFilter::ContainsIgnoreCase(value),
+            StringFilterInput::InIgnoreCase(values) => StringFilter::InIgnoreCase(values),
+            StringFilterInput::IsNull(true) => StringFilter::IsNull,
+            StringFilterInput::IsNull(false) => StringFilter::IsNotNull,
+        }
+    }
+}
+
+#[derive(OneofObject)]
+pub(crate) enum BooleanFilterInput {
+    Eq(bool),
+    IsNull(bool),
+}
+
+impl GraphqlFilter for BooleanFilter {
+    type Input = BooleanFilterInput;
+
+    fn from_input(input: Self::Input) -> Self {
+        match input {
+            BooleanFilterInput::Eq(true) => BooleanFilter::True,
+            BooleanFilterInput::Eq(false) => BooleanFilter::False,
+            BooleanFilterInput::IsNull(true) => BooleanFilter::IsNull,
+            BooleanFilterInput::IsNull(false) => BooleanFilter::IsNotNull,
+        }
+    }
+}
+
+#[derive(InputObject)]
+pub(crate) struct DurationInput {
+    number: u32,
+    unit: TimeUnit,
+}
+
+// Date time filter and range inputs for a chrono type
+macro_rules! date_time_filter_input {
+    ($input:ident, $range_input:ident, $type:ty) => {
+        // Inclusive
+        #[derive(InputObject)]
+        pub(crate) struct $range_input {
+            from: $type,
+            to: $type,
+        }
+
+        #[derive(OneofObject)]
+        pub(crate) enum $input {
+            Eq($type),
+            Before($type),
+            After($type),
+            Between($range_input),
+            OnDay(NaiveDate),
+            Last(DurationInput),
+            OlderThan(DurationInput),
+            This(Period),
+            IsNull(bool),
+        }
+
+        impl GraphqlFilter for DateTimeFilter<$type> {
+            type Input = $input;
+
+            fn from_input(input: Self::Input) -> Self {
+                match input {
+                    $input::Eq(value) => DateTimeFilter::Equal(value),
+                    $input::Before(value) => DateTimeFilter::Before(value),
+                    $input::After(value) => DateTimeFilter::After(value),
+                    $input::Between(range) => DateTimeFilter::Between {
+                        from: range.from,
+                        to: range.to,
+                    },
+                    $input::OnDay(day) => DateTimeFilter::OnDay(day),
+                    $input::Last(duration) => DateTimeFilter::Last(duration.number, duration.unit),
+                    $input::OlderThan(duration) => {
+                        DateTimeFilter::OlderThan(duration.number, duration.unit)
+                    }
+                    $input::This(period) => DateTimeFilter::This(period),
+                    $input::IsNull(true) => DateTimeFilter::IsNull,
+                    $input::IsNull(false) => DateTimeFilter::IsNotNull,
+                }
+            }
+        }
+    };
+}
+
+date_time_filter_input!(DateFilterInput, DateRangeInput, NaiveDate);
+date_time_filter_input!(TimeFilterInput, TimeRangeInput, NaiveTime);
+date_time_filter_input!(DateTimeFilterInput, DateTimeRangeInput, NaiveDateTime);
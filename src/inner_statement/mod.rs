@@ -1,14 +1,20 @@
-use diesel::{connection::SimpleConnection, prelude::*, SqliteConnection};
-
-use crate::inner_statement::bike::create_filtered_query;
-
 mod bike;
 mod bike_trip;
 mod person;
+mod road;
 
-#[test]
-fn test() {
-    let mut connection = SqliteConnection::establish("file:test?mode=memory&cache=shared").unwrap();
+#[cfg(test)]
+use diesel::{connection::SimpleConnection, prelude::*, SqliteConnection};
+
+#[cfg(test)]
+use super::*;
+
+// New in memory database, shared by name (for a second connection to it), with bikes of craig and
+// mark (m2 without color) and trips on them over roads with cycle lanes
+#[cfg(test)]
+fn connection(name: &str) -> SqliteConnection {
+    let mut connection =
+        SqliteConnection::establish(&format!("file:{name}?mode=memory&cache=shared")).unwrap();
 
     connection
         .batch_execute(
@@ -30,138 +36,775 @@ fn test() {
                 color_id TEXT REFERENCES color(id)
             );
 
-            INSERT INTO color 
-              (id, name) 
-            VALUES
-              ('orange', 'orange');
-            
-            INSERT INTO color 
-              (id, name) 
-            VALUES
-              ('purple', 'purple');
-            
-            INSERT INTO color 
-              (id, name) 
+            CREATE TABLE road (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL
+            );
+
+            CREATE TABLE bike_trip (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                road_id TEXT REFERENCES road(id),
+                bike_id TEXT REFERENCES bike(id)
+            );
+
+            CREATE TABLE cycle_lane (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                road_id TEXT REFERENCES road(id)
+            );
+
+            CREATE TABLE road_on_bike_trip (
+                id TEXT PRIMARY KEY,
+                bike_trip_id TEXT REFERENCES bike_trip(id),
+                road_id TEXT REFERENCES road(id)
+            );
+
+            INSERT INTO color
+              (id, name)
             VALUES
+              ('orange', 'orange'),
+              ('purple', 'purple'),
               ('grey', 'grey');
 
-            INSERT INTO person 
-              (id, name) 
-            VALUES
-              ('craig', 'craig');
-            
-            INSERT INTO person 
-              (id, name) 
+            INSERT INTO person
+              (id, name)
             VALUES
+              ('craig', 'craig'),
               ('mark', 'mark');
 
-            INSERT INTO bike 
-              (id, name, owner_id, color_id) 
+            INSERT INTO bike
+              (id, name, owner_id, color_id)
             VALUES
-              ('c1', 'c1', 'craig', 'orange');
-            
-            INSERT INTO bike 
-              (id, name, owner_id, color_id) 
+              ('c1', 'c1', 'craig', 'orange'),
+              ('c2', 'c2', 'craig', 'purple'),
+              ('m1', 'm1', 'mark', 'grey'),
+              ('m2', 'm2', 'mark', NULL);
+
+            INSERT INTO road
+              (id, name)
+            VALUES
+              ('tamaki', 'tamaki'),
+              ('queen', 'queen');
+
+            INSERT INTO cycle_lane
+              (id, name, road_id)
+            VALUES
+              ('upper_queen_lane', 'upper_queen_lane', 'queen'),
+              ('lower_queen_lane', 'lower_queen_lane', 'queen'),
+              ('bendy', 'bendy', 'tamaki'),
+              ('flatty', 'flatty', 'tamaki'),
+              ('windy', 'windy', 'tamaki');
+
+            INSERT INTO bike_trip
+              (id, name, road_id, bike_id)
             VALUES
-              ('c2', 'c2', 'craig', 'purple');
+              ('t1', 't1', 'queen', 'c1'),
+              ('t2', 't2', 'tamaki', 'm1');
 
-            INSERT INTO bike 
-              (id, name, owner_id, color_id) 
+            INSERT INTO road_on_bike_trip
+              (id, bike_trip_id, road_id)
             VALUES
-              ('m1', 'm1', 'mark', 'grey');
+              ('t1_queen', 't1', 'queen'),
+              ('t2_tamaki', 't2', 'tamaki'),
+              ('t2_queen', 't2', 'queen');
         "#,
         )
         .unwrap();
-    use super::*;
+    connection
+}
+
+#[test]
+fn inner_statement_test() {
+    use self::person::*;
 
-    {
-        use self::person::*;
+    let mut connection = connection("inner_statement_test");
 
-        let condition = vec![Condition::bike(vec![bike::Condition::color(
-            StringFilter::In(vec!["orange".to_string(), "purple".to_string()]),
-        )])];
-        let result = vec!["craig".to_string()];
+    let condition = vec![Condition::bike(vec![bike::Condition::color(
+        StringFilter::In(vec!["orange".to_string(), "purple".to_string()]),
+    )])];
+    let result = vec!["craig".to_string()];
 
+    assert_eq!(
+        result,
+        create_filtered_query(condition)
+            .unwrap()
+            .select(person::dsl::id)
+            .load::<String>(&mut connection)
+            .unwrap()
+    );
+
+    // Color is null for bikes without color, via left join
+    let condition = vec![Condition::bike(vec![bike::Condition::color(
+        StringFilter::IsNull,
+    )])];
+    let result = vec!["mark".to_string()];
+
+    assert_eq!(
+        result,
+        create_filtered_query(condition)
+            .unwrap()
+            .select(person::dsl::id)
+            .load::<String>(&mut connection)
+            .unwrap()
+    );
+}
+
+#[test]
+fn exists_test() {
+    use self::person::*;
+
+    let mut connection = connection("exists_test");
+
+    // Correlated EXISTS and NOT EXISTS, i.e. people without a grey bike
+    let cases = vec![
+        (
+            Condition::bike_any(vec![bike::Condition::color(StringFilter::Equal(
+                "grey".to_string(),
+            ))]),
+            vec!["mark"],
+        ),
+        (
+            Condition::bike_none(vec![bike::Condition::color(StringFilter::Equal(
+                "grey".to_string(),
+            ))]),
+            vec!["craig"],
+        ),
+        (
+            Condition::bike_none(vec![bike::Condition::color(StringFilter::IsNull)]),
+            vec!["craig"],
+        ),
+        (Condition::bike_any(vec![]), vec!["craig", "mark"]),
+        (Condition::bike_none(vec![]), vec![]),
+        (
+            Condition::Not(Box::new(Condition::bike_any(vec![bike::Condition::name(
+                StringFilter::Equal("c1".to_string()),
+            )]))),
+            vec!["mark"],
+        ),
+    ];
+
+    for (condition, result) in cases {
         assert_eq!(
             result,
-            create_filtered_query(condition)
+            create_filtered_query(vec![condition])
+                .unwrap()
                 .select(person::dsl::id)
+                .order_by(person::dsl::id)
                 .load::<String>(&mut connection)
                 .unwrap()
         );
     }
+
+    // Correlation within a query of bike joined to color, bike of the EXISTS subquery is the
+    // inner one and person the one of the enclosing person query
+    let owners_of_grey_bikes =
+        create_filtered_query(vec![Condition::bike_any(vec![bike::Condition::color(
+            StringFilter::Equal("grey".to_string()),
+        )])])
+        .unwrap()
+        .select(person::dsl::id);
+    let result = vec!["m1".to_string(), "m2".to_string()];
+
+    assert_eq!(
+        result,
+        bike::create_filtered_query(vec![])
+            .unwrap()
+            .filter(bike::bike::dsl::owner_id.eq_any(owners_of_grey_bikes))
+            .select(bike::bike::dsl::id)
+            .order_by(bike::bike::dsl::id)
+            .load::<String>(&mut connection)
+            .unwrap()
+    );
+}
+
+#[test]
+fn all_test() {
+    use self::person::*;
+
+    let mut connection = connection("all_test");
+
+    // A person without bikes
     connection
         .batch_execute(
             r#"
-            CREATE TABLE road (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL
-            );
+            INSERT INTO person
+              (id, name)
+            VALUES
+              ('lisa', 'lisa');
+        "#,
+        )
+        .unwrap();
 
-            CREATE TABLE bike_trip (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                road_id TEXT REFERENCES road(id),
-                bike_id TEXT REFERENCES bike(id)
-            );
+    let color = |filter: StringFilter| bike::Condition::color(filter);
+    let cases = vec![
+        (
+            vec![Condition::bike_all(vec![color(StringFilter::In(vec![
+                "orange".to_string(),
+                "purple".to_string(),
+            ]))])],
+            vec!["craig", "lisa"],
+        ),
+        (
+            vec![Condition::bike_all(vec![color(StringFilter::Equal(
+                "orange".to_string(),
+            ))])],
+            vec!["lisa"],
+        ),
+        // m2 has no color, NULL != 'purple' is unknown rather than a match
+        (
+            vec![Condition::bike_all(vec![color(StringFilter::NotEqual(
+                "purple".to_string(),
+            ))])],
+            vec!["lisa"],
+        ),
+        (
+            vec![Condition::bike_all(vec![bike::Condition::Or(vec![
+                color(StringFilter::Equal("grey".to_string())),
+                color(StringFilter::IsNull),
+            ])])],
+            vec!["lisa", "mark"],
+        ),
+        (
+            vec![Condition::bike_all(vec![bike::Condition::Not(Box::new(
+                color(StringFilter::Equal("grey".to_string())),
+            ))])],
+            vec!["craig", "lisa"],
+        ),
+        (
+            vec![Condition::bike_all(vec![])],
+            vec!["craig", "lisa", "mark"],
+        ),
+        // And at least one bike
+        (
+            vec![
+                Condition::bike_all(vec![color(StringFilter::IsNotNull)]),
+                Condition::bike_any(vec![]),
+            ],
+            vec!["craig"],
+        ),
+    ];
 
-            CREATE TABLE cycle_lane (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                road_id TEXT REFERENCES road(id)
-            );
+    for (condition, result) in cases {
+        assert_eq!(
+            result,
+            create_filtered_query(condition)
+                .unwrap()
+                .select(person::dsl::id)
+                .order_by(person::dsl::id)
+                .load::<String>(&mut connection)
+                .unwrap()
+        );
+    }
+}
 
-            INSERT INTO road 
-              (id, name) 
-            VALUES
-              ('tamaki', 'tamaki');
-            
-            INSERT INTO road 
-              (id, name) 
-            VALUES
-              ('queen', 'queen');
+#[test]
+fn count_test() {
+    use self::person::*;
 
-            INSERT INTO cycle_lane 
-              (id, name, road_id) 
-            VALUES
-              ('upper_queen_lane', 'upper_queen_lane', 'queen');
+    let mut connection = connection("count_test");
 
-            INSERT INTO cycle_lane 
-              (id, name, road_id) 
-            VALUES
-              ('lower_queen_lane', 'lower_queen_lane', 'queen');
-            
-            INSERT INTO cycle_lane 
-              (id, name, road_id) 
+    // A person without bikes
+    connection
+        .batch_execute(
+            r#"
+            INSERT INTO person
+              (id, name)
             VALUES
-              ('bendy', 'bendy', 'tamaki');
+              ('lisa', 'lisa');
+        "#,
+        )
+        .unwrap();
 
-            INSERT INTO cycle_lane 
-              (id, name, road_id) 
-            VALUES
-              ('flatty', 'flatty', 'tamaki');
+    let color = |filter: StringFilter| bike::Condition::color(filter);
 
-            INSERT INTO cycle_lane 
-              (id, name, road_id) 
-            VALUES
-              ('windy', 'windy', 'tamaki');    
+    // Number of matching bikes, as correlated COUNT(*)
+    let count = |conditions, count| Condition::bike_count(RelationCount { conditions, count });
+    let cases = vec![
+        (count(vec![], NumberFilter::Equal(0)), vec!["lisa"]),
+        (
+            count(
+                vec![color(StringFilter::In(vec![
+                    "orange".to_string(),
+                    "purple".to_string(),
+                ]))],
+                NumberFilter::GreaterOrEqual(2),
+            ),
+            vec!["craig"],
+        ),
+        (
+            count(vec![color(StringFilter::IsNull)], NumberFilter::Equal(1)),
+            vec!["mark"],
+        ),
+        (
+            count(
+                vec![],
+                NumberFilter::Between {
+                    from: 1,
+                    to: 2,
+                    from_inclusive: true,
+                    to_inclusive: true,
+                },
+            ),
+            vec!["craig", "mark"],
+        ),
+        (
+            count(
+                vec![],
+                NumberFilter::Between {
+                    from: 1,
+                    to: 3,
+                    from_inclusive: false,
+                    to_inclusive: false,
+                },
+            ),
+            vec!["craig", "mark"],
+        ),
+        (
+            count(vec![], NumberFilter::NotIn(vec![0, 1])),
+            vec!["craig", "mark"],
+        ),
+    ];
 
-            
-            INSERT INTO bike_trip 
-              (id, name, road_id, bike_id) 
-            VALUES
-              ('t1', 't1', 'queen', 'c1');   
+    for (condition, result) in cases {
+        assert_eq!(
+            result,
+            create_filtered_query(vec![condition])
+                .unwrap()
+                .select(person::dsl::id)
+                .order_by(person::dsl::id)
+                .load::<String>(&mut connection)
+                .unwrap()
+        );
+    }
+
+    // Between with exclusive bounds counts in a single subquery
+    let condition = count(
+        vec![],
+        NumberFilter::Between {
+            from: 0,
+            to: 2,
+            from_inclusive: false,
+            to_inclusive: true,
+        },
+    );
+    let query = create_filtered_query(vec![condition])
+        .unwrap()
+        .select(person::dsl::id);
+    let sql = diesel::debug_query::<diesel::sqlite::Sqlite, _>(&query).to_string();
+
+    assert_eq!(sql.matches("COUNT(*)").count(), 1, "{}", sql);
+    assert!(sql.contains("BETWEEN"), "{}", sql);
+
+    // Count is a condition among inner statement conditions in text syntax
+    let input = r#"bike_count(color = "grey" and count >= 1) or bike_count.count = 0"#;
+    let condition: Vec<Condition> = crate::syntax::text::parse(input).unwrap();
+    assert_eq!(crate::syntax::text::print(&condition), input);
+    let result = vec!["lisa".to_string(), "mark".to_string()];
+
+    assert_eq!(
+        result,
+        create_filtered_query(condition)
+            .unwrap()
+            .select(person::dsl::id)
+            .order_by(person::dsl::id)
+            .load::<String>(&mut connection)
+            .unwrap()
+    );
+    assert!(crate::syntax::text::parse::<Field>(r#"bike_count(color = "grey")"#).is_err());
+}
+
+#[test]
+fn syntax_test() {
+    use self::person::*;
+
+    let mut connection = connection("syntax_test");
+
+    // Text syntax, with path to bike field
+    let input = r#"bike.color in ("orange", "purple") or bike(name = "m2" and color is null)"#;
+    let condition: Vec<Condition> = crate::syntax::text::parse(input).unwrap();
+    assert_eq!(crate::syntax::text::print(&condition), input);
+    let result = vec!["craig".to_string(), "mark".to_string()];
+
+    assert_eq!(
+        result,
+        create_filtered_query(condition)
+            .unwrap()
+            .select(person::dsl::id)
+            .order_by(person::dsl::id)
+            .load::<String>(&mut connection)
+            .unwrap()
+    );
+    assert!(crate::syntax::text::parse::<Field>("bike.colour = \"grey\"").is_err());
+
+    // Query string, with inner statement conditions nested under bike
+    let query_string = "bike[color][in][0]=orange&bike[color][in][1]=purple";
+    let condition: Vec<Condition> = crate::syntax::query_string::decode(query_string).unwrap();
+    assert_eq!(
+        crate::syntax::query_string::encode(&condition),
+        query_string
+    );
+    let result = vec!["craig".to_string()];
+
+    assert_eq!(
+        result,
+        create_filtered_query(condition)
+            .unwrap()
+            .select(person::dsl::id)
+            .load::<String>(&mut connection)
+            .unwrap()
+    );
+
+    // RSQL for bike table, and with dotted selector for person
+    let input = "name==c*;color=in=(orange,purple),color=isnull=true";
+    let condition: Vec<bike::Condition> = crate::syntax::rsql::parse(input).unwrap();
+    let result = vec!["c1".to_string(), "c2".to_string(), "m2".to_string()];
+
+    assert_eq!(
+        result,
+        bike::create_filtered_query(condition)
+            .unwrap()
+            .select(bike::bike::dsl::id)
+            .order_by(bike::bike::dsl::id)
+            .load::<String>(&mut connection)
+            .unwrap()
+    );
+
+    let condition: Vec<Condition> = crate::syntax::rsql::parse("bike.color==grey").unwrap();
+    let result = vec!["mark".to_string()];
+
+    assert_eq!(
+        result,
+        create_filtered_query(condition)
+            .unwrap()
+            .select(person::dsl::id)
+            .load::<String>(&mut connection)
+            .unwrap()
+    );
+
+    // OData navigation property
+    let filter = "bike/color eq 'grey' or bike/name eq 'c1'";
+    let condition: Vec<Condition> = crate::syntax::odata::parse(filter).unwrap();
+    let result = vec!["craig".to_string(), "mark".to_string()];
+
+    assert_eq!(
+        result,
+        create_filtered_query(condition)
+            .unwrap()
+            .select(person::dsl::id)
+            .order_by(person::dsl::id)
+            .load::<String>(&mut connection)
+            .unwrap()
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn mongo_test() {
+    use self::person::*;
+
+    let mut connection = connection("mongo_test");
+
+    // MongoDB style $elemMatch on bike
+    let query = serde_json::json!({
+        "name": {"$regex": "C", "$options": "i"},
+        "bike": {"$elemMatch": {"color": {"$in": ["orange", "purple"]}}},
+    });
+    let condition: Vec<Condition> = crate::syntax::mongo::decode(&query).unwrap();
+    let result = vec!["craig".to_string()];
 
-            INSERT INTO bike_trip 
-              (id, name, road_id, bike_id) 
+    assert_eq!(
+        result,
+        create_filtered_query(condition)
+            .unwrap()
+            .select(person::dsl::id)
+            .load::<String>(&mut connection)
+            .unwrap()
+    );
+
+    // Negated $elemMatch is bike_none, a bike without owner doesn't turn NOT IN unknown
+    connection
+        .batch_execute(
+            r#"
+            INSERT INTO bike
+              (id, name, owner_id, color_id)
             VALUES
-              ('t2', 't2', 'tamaki', 'm1');   
+              ('x1', 'x1', NULL, 'grey');
         "#,
         )
         .unwrap();
 
+    let query = serde_json::json!({
+        "$nor": [{"bike": {"$elemMatch": {"color": "grey"}}}],
+    });
+    let condition: Vec<Condition> = crate::syntax::mongo::decode(&query).unwrap();
+    let result = vec!["craig".to_string()];
+
+    assert_eq!(
+        result,
+        create_filtered_query(condition)
+            .unwrap()
+            .select(person::dsl::id)
+            .load::<String>(&mut connection)
+            .unwrap()
+    );
+
+    connection
+        .batch_execute("DELETE FROM bike WHERE id = 'x1';")
+        .unwrap();
+}
+
+#[test]
+fn sort_test() {
+    use self::person::*;
+
+    let mut connection = connection("sort_test");
+
+    // Sort travelling with filter, and sorting bikes by joined color
+    let request = ListRequest {
+        filter: vec![Condition::name(StringFilter::In(vec![
+            "craig".to_string(),
+            "mark".to_string(),
+        ]))],
+        sort: vec![Sort::desc(SortField::name)],
+    };
+    let result = vec!["mark".to_string(), "craig".to_string()];
+
+    assert_eq!(
+        result,
+        create_list_query(request)
+            .unwrap()
+            .select(person::dsl::id)
+            .load::<String>(&mut connection)
+            .unwrap()
+    );
+
+    let cases = vec![
+        (
+            vec![bike::Sort::asc(bike::SortField::color)],
+            vec!["m2", "m1", "c1", "c2"],
+        ),
+        (
+            vec![bike::Sort::asc(bike::SortField::color).nulls(NullsOrder::Last)],
+            vec!["m1", "c1", "c2", "m2"],
+        ),
+        (
+            vec![bike::Sort::desc(bike::SortField::color)],
+            vec!["c2", "c1", "m1", "m2"],
+        ),
+        (
+            vec![bike::Sort::desc(bike::SortField::color).nulls(NullsOrder::First)],
+            vec!["m2", "c2", "c1", "m1"],
+        ),
+        (
+            vec![
+                bike::Sort::desc(bike::SortField::owner),
+                bike::Sort::desc(bike::SortField::name),
+            ],
+            vec!["m2", "m1", "c2", "c1"],
+        ),
+    ];
+
+    for (sort, result) in cases {
+        let query = bike::apply_sort(bike::create_filtered_query(vec![]).unwrap(), sort.clone());
+
+        assert_eq!(
+            result,
+            query
+                .select(bike::bike::dsl::id)
+                .load::<String>(&mut connection)
+                .unwrap()
+        );
+
+        // Keyset pages add up to the same order, NULL colors and equal owners included
+        for limit in [1, 3] {
+            let mut ids = Vec::new();
+            let mut cursor = None;
+            loop {
+                let request = ListRequest {
+                    filter: vec![],
+                    sort: sort.clone(),
+                };
+                let rows = bike::create_page_query(request, cursor.as_deref(), limit)
+                    .unwrap()
+                    .select((bike::bike::dsl::id, bike::sort_selection()))
+                    .load::<(String, bike::SortRow)>(&mut connection)
+                    .unwrap();
+                let page = bike::next_page(rows, sort.clone(), limit);
+
+                assert_eq!(page.has_more, page.next_cursor.is_some());
+                ids.extend(page.items);
+                cursor = page.next_cursor;
+                if !page.has_more {
+                    break;
+                }
+            }
+
+            assert_eq!(result, ids);
+        }
+    }
+
+    // Cursor of another sort
+    let request = ListRequest {
+        filter: vec![],
+        sort: vec![bike::Sort::asc(bike::SortField::color)],
+    };
+
+    assert_eq!(
+        bike::create_page_query(request, Some("v6d32"), 1).err(),
+        Some(FilterError::InvalidCursor)
+    );
+
+    // Options are passed on to the filter, and the extra row of i64::MAX doesn't overflow
+    let options = FilterOptions {
+        empty_group: EmptyGroup::Reject,
+        ..Default::default()
+    };
+    let request = ListRequest {
+        filter: vec![bike::Condition::Or(vec![])],
+        sort: vec![],
+    };
+
+    assert_eq!(
+        bike::create_page_query_with(request, None, 1, &options).err(),
+        Some(FilterError::EmptyGroup)
+    );
+
+    let request = ListRequest {
+        filter: vec![],
+        sort: vec![],
+    };
+    let rows = bike::create_page_query_with(request, None, i64::MAX, &options)
+        .unwrap()
+        .select((bike::bike::dsl::id, bike::sort_selection()))
+        .load::<(String, bike::SortRow)>(&mut connection)
+        .unwrap();
+    let page = bike::next_page(rows, vec![], i64::MAX);
+
+    assert_eq!(vec!["c1", "c2", "m1", "m2"], page.items);
+    assert!(!page.has_more);
+}
+
+#[test]
+fn page_test() {
+    use self::person::*;
+
+    let mut connection = connection("page_test");
+
+    // Offset page and total count of the same conditions
+    let condition = vec![bike::Condition::color(StringFilter::IsNotNull)];
+    let page = bike::load_page(&mut connection, condition, 2, 1, |query| {
+        query
+            .select(bike::bike::dsl::id)
+            .order_by(bike::bike::dsl::id)
+    })
+    .unwrap();
+    let result = Page {
+        items: vec!["c2".to_string(), "m1".to_string()],
+        total: 3,
+        limit: 2,
+        offset: 1,
+    };
+
+    assert_eq!(result, page);
+
+    let condition = vec![Condition::bike(vec![bike::Condition::color(
+        StringFilter::Equal("grey".to_string()),
+    )])];
+
+    assert_eq!(
+        1,
+        create_filtered_count(condition)
+            .unwrap()
+            .get_result::<i64>(&mut connection)
+            .unwrap()
+    );
+}
+
+#[cfg(feature = "async-graphql")]
+#[test]
+fn graphql_test() {
+    use self::person::*;
+    use async_graphql::{Context, EmptyMutation, EmptySubscription, Schema};
+    use std::sync::Mutex;
+
+    // Keeps the shared in memory database open for the GraphQL connection to it
+    let _connection = connection("graphql_test");
+
+    struct Query;
+
+    #[async_graphql::Object]
+    impl Query {
+        async fn persons(
+            &self,
+            context: &Context<'_>,
+            filter: ConditionInput,
+        ) -> async_graphql::Result<Vec<String>> {
+            let mut connection = context
+                .data_unchecked::<Mutex<SqliteConnection>>()
+                .lock()
+                .unwrap();
+            let query =
+                create_filtered_query(vec![filter.into()]).map_err(|error| format!("{error:?}"))?;
+            Ok(query
+                .select(person::dsl::id)
+                .order_by(person::dsl::id)
+                .load::<String>(&mut *connection)?)
+        }
+    }
+
+    let graphql_connection =
+        SqliteConnection::establish("file:graphql_test?mode=memory&cache=shared").unwrap();
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(Mutex::new(graphql_connection))
+        .finish();
+    let execute = |query: &str| {
+        let response = futures_executor::block_on(schema.execute(query));
+        (
+            response.data.into_json().unwrap(),
+            response
+                .errors
+                .into_iter()
+                .map(|error| error.message)
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    let (data, errors) =
+        execute(r#"{ persons(filter: { bike: { color: { in: ["orange", "purple"] } } }) }"#);
+    assert_eq!(errors, Vec::<String>::new());
+    assert_eq!(data, serde_json::json!({"persons": ["craig"]}));
+
+    let (data, errors) = execute(
+        r#"{ persons(filter: {
+            or: [
+                { bike: { and: [{ name: { eq: "m2" } }, { color: { isNull: true } }] } },
+                { not: { name: { startsWith: "m" } } }
+            ]
+        }) }"#,
+    );
+    assert_eq!(errors, Vec::<String>::new());
+    assert_eq!(data, serde_json::json!({"persons": ["craig", "mark"]}));
+
+    let (data, errors) = execute(
+        r#"{ persons(filter: {
+            bikeCount: { conditions: { color: { eq: "grey" } }, count: { gte: 1 } }
+        }) }"#,
+    );
+    assert_eq!(errors, Vec::<String>::new());
+    assert_eq!(data, serde_json::json!({"persons": ["mark"]}));
+
+    // Filters are oneOf, exactly one operator
+    let (_, errors) = execute(r#"{ persons(filter: { name: { eq: "mark", startsWith: "m" } }) }"#);
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn bike_trip_test() {
     use self::bike_trip::*;
 
+    let mut connection = connection("bike_trip_test");
+
     let condition = vec![Condition::bike(StringFilter::Equal("m1".to_string()))];
     let result = vec![
         Some("bendy".to_string()),
@@ -172,9 +815,188 @@ fn test() {
     assert_eq!(
         result,
         create_filtered_query(condition)
+            .unwrap()
             .select(cycle_lane::dsl::name.nullable())
             .order_by(cycle_lane::dsl::name)
             .load::<Option<String>>(&mut connection)
             .unwrap()
     );
+
+    // Multi key sort, by inner joined bike and left joined cycle lane
+    let request = ListRequest {
+        filter: vec![],
+        sort: vec![
+            Sort::asc(SortField::bike),
+            Sort::desc(SortField::cycle_lane),
+        ],
+    };
+    let result = vec![
+        ("t1".to_string(), Some("upper_queen_lane".to_string())),
+        ("t1".to_string(), Some("lower_queen_lane".to_string())),
+        ("t2".to_string(), Some("windy".to_string())),
+        ("t2".to_string(), Some("flatty".to_string())),
+        ("t2".to_string(), Some("bendy".to_string())),
+    ];
+
+    assert_eq!(
+        result,
+        create_list_query(request)
+            .unwrap()
+            .select((bike_trip::dsl::id, cycle_lane::dsl::name.nullable()))
+            .load::<(String, Option<String>)>(&mut connection)
+            .unwrap()
+    );
+
+    // Keyset pages of trips by bike, rows of the same trip are told apart by cycle lane id
+    let sort = vec![Sort::asc(SortField::bike)];
+    let request = ListRequest {
+        filter: vec![],
+        sort: sort.clone(),
+    };
+    let rows = create_page_query(request, None, 2)
+        .unwrap()
+        .select((cycle_lane::dsl::id.nullable(), sort_selection()))
+        .load::<(Option<String>, SortRow)>(&mut connection)
+        .unwrap();
+    let page = next_page(rows, sort.clone(), 2);
+    let result = vec![
+        Some("lower_queen_lane".to_string()),
+        Some("upper_queen_lane".to_string()),
+    ];
+
+    assert_eq!(result, page.items);
+    assert!(page.has_more);
+
+    let request = ListRequest {
+        filter: vec![],
+        sort: sort.clone(),
+    };
+    let rows = create_page_query(request, page.next_cursor.as_deref(), 2)
+        .unwrap()
+        .select((cycle_lane::dsl::id.nullable(), sort_selection()))
+        .load::<(Option<String>, SortRow)>(&mut connection)
+        .unwrap();
+    let page = next_page(rows, sort, 2);
+    let result = vec![Some("bendy".to_string()), Some("flatty".to_string())];
+
+    assert_eq!(result, page.items);
+    assert!(page.has_more);
+}
+
+#[test]
+fn many_to_many_test() {
+    use self::bike_trip::*;
+
+    let mut connection = connection("many_to_many_test");
+
+    // Many to many roads through road_on_bike_trip, t1 has one road, t2 has two and t3 has none
+    // (link rows with NULL trip or road don't link anything)
+    connection
+        .batch_execute(
+            r#"
+            INSERT INTO bike_trip
+              (id, name, road_id, bike_id)
+            VALUES
+              ('t3', 't3', NULL, 'm2');
+
+            INSERT INTO road_on_bike_trip
+              (id, bike_trip_id, road_id)
+            VALUES
+              ('no_trip_queen', NULL, 'queen');
+
+            INSERT INTO road_on_bike_trip
+              (id, bike_trip_id, road_id)
+            VALUES
+              ('t3_no_road', 't3', NULL);
+        "#,
+        )
+        .unwrap();
+
+    let trips = |condition: Vec<Condition>, connection: &mut SqliteConnection| {
+        create_filtered_query(condition)
+            .unwrap()
+            .select(bike_trip::dsl::id)
+            .distinct()
+            .order_by(bike_trip::dsl::id)
+            .load::<String>(connection)
+            .unwrap()
+    };
+
+    let condition = vec![Condition::road(vec![road::Condition::name(
+        StringFilter::Equal("queen".to_string()),
+    )])];
+    assert_eq!(vec!["t1", "t2"], trips(condition, &mut connection));
+
+    let condition = vec![Condition::road(vec![road::Condition::name(
+        StringFilter::Equal("tamaki".to_string()),
+    )])];
+    assert_eq!(vec!["t2"], trips(condition, &mut connection));
+
+    // Trips with any road, and with no roads
+    let condition = vec![Condition::road(vec![])];
+    assert_eq!(vec!["t1", "t2"], trips(condition, &mut connection));
+
+    let condition = vec![Condition::Not(Box::new(Condition::road(vec![])))];
+    assert_eq!(vec!["t3"], trips(condition, &mut connection));
+
+    // NOT IN of trips on queen would be unknown for every trip, as there's a NULL trip on it
+    let condition = vec![Condition::Not(Box::new(Condition::road(vec![
+        road::Condition::name(StringFilter::Equal("queen".to_string())),
+    ])))];
+    assert_eq!(vec!["t3"], trips(condition, &mut connection));
+
+    // Each road condition is matched by any of the roads
+    let condition = vec![
+        Condition::road(vec![road::Condition::name(StringFilter::Equal(
+            "queen".to_string(),
+        ))]),
+        Condition::road(vec![road::Condition::name(StringFilter::Equal(
+            "tamaki".to_string(),
+        ))]),
+    ];
+    assert_eq!(vec!["t2"], trips(condition, &mut connection));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_test() {
+    use crate::{ListRequest, NullsOrder};
+    use serde_json::json;
+
+    // Inner statement conditions are nested as is
+    let json = json!([
+        {"name": {"starts_with": "c"}},
+        {"bike": [
+            {"color": {"in": ["orange", "purple"]}},
+            {"not": {"name": "is_null"}},
+        ]},
+        {"bike_count": {"conditions": [{"color": {"eq": "grey"}}], "count": {"gte": 2}}},
+    ]);
+
+    let conditions: Vec<person::Condition> = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(json, serde_json::to_value(&conditions).unwrap());
+
+    let json = json!({"or": [
+        {"bike": {"eq": "m1"}},
+        {"cycle_lane": {"contains_ignore_case": "lane"}},
+        {"road": [{"name": {"eq": "queen"}}]},
+    ]});
+
+    let condition: bike_trip::Condition = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(json, serde_json::to_value(&condition).unwrap());
+
+    // Direction defaults to asc
+    let json = json!({
+        "filter": [{"color": {"in": ["orange", "purple"]}}],
+        "sort": [{"field": "color", "direction": "desc", "nulls": "last"}, {"field": "name"}],
+    });
+
+    let request: ListRequest<bike::Condition, bike::Sort> = serde_json::from_value(json).unwrap();
+    assert_eq!(
+        request.sort,
+        vec![
+            bike::Sort::desc(bike::SortField::color).nulls(NullsOrder::Last),
+            bike::Sort::asc(bike::SortField::name),
+        ]
+    );
 }
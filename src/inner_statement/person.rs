@@ -1,9 +1,4 @@
-use diesel::{
-    helper_types::IntoBoxed,
-    prelude::*,
-    sql_types::{Bool, Nullable},
-    sqlite::Sqlite,
-};
+use diesel::prelude::*;
 
 use super::*;
 use crate::*;
@@ -15,69 +10,79 @@ table! {
     }
 }
 
-#[allow(non_camel_case_types)]
-pub(super) enum Condition {
-    name(StringFilter),
-    bike(Vec<bike::Condition>),
-    And(Vec<Condition>),
-    Or(Vec<Condition>),
-}
-
-type ConditionSource = person::dsl::person;
-// Need this type for common condition expressions
-type BoxedCondition = Box<dyn BoxableExpression<ConditionSource, Sqlite, SqlType = Nullable<Bool>>>;
-
-type QuerySource = person::dsl::person;
-type BoxedQuery = IntoBoxed<'static, QuerySource, Sqlite>;
-
-impl Condition {
-    fn to_boxed_condition(self) -> Option<BoxedCondition> {
-        Some(match self {
-            Condition::name(f) => string_filter!(f, person::dsl::name),
-            Condition::And(conditions) => match create_filter(conditions, AndOr::And) {
-                Some(boxed_condition) => boxed_condition,
-                None => return None,
-            },
-            Condition::Or(conditions) => match create_filter(conditions, AndOr::Or) {
-                Some(boxed_condition) => boxed_condition,
-                None => return None,
-            },
-            Condition::bike(conditions) => {
-                // Inner statement, reusing conditions defined in bike
-                let inner_statement = bike::create_filtered_query(conditions);
-                Box::new(
-                    person::dsl::id
-                        .eq_any(inner_statement.select(bike::bike::dsl::owner_id))
-                        .nullable(),
-                )
-            }
-        })
+dynamic_filter! {
+    pub(super) Condition,
+    Backend = diesel::sqlite::Sqlite,
+    ConditionSource = person::dsl::person,
+    QuerySource = person::dsl::person,
+    query = person::dsl::person,
+    fields {
+        name(StringFilter) => |f| string_filter!(f, person::dsl::name),
+        bike(Vec<bike::Condition>) => |conditions, options| {
+            // Inner statement, reusing conditions defined in bike
+            let inner_statement = bike::create_filtered_query_with(conditions, options)?;
+            Box::new(
+                person::dsl::id
+                    .eq_any(inner_statement.select(bike::bike::dsl::owner_id))
+                    .nullable(),
+            )
+        },
+        // Correlated EXISTS, which can be negated even with NULL owner_id (NOT IN with a NULL in
+        // the list is never TRUE)
+        bike_any(Vec<bike::Condition>) => |conditions, options| {
+            let owned_bikes = bike::create_filtered_query_with(conditions, options)?
+                .filter(correlate(bike::bike::dsl::owner_id, person::dsl::id));
+            Box::new(dsl::exists(owned_bikes).nullable())
+        },
+        bike_none(Vec<bike::Condition>) => |conditions, options| {
+            let owned_bikes = bike::create_filtered_query_with(conditions, options)?
+                .filter(correlate(bike::bike::dsl::owner_id, person::dsl::id));
+            Box::new(dsl::not(dsl::exists(owned_bikes)).nullable())
+        },
+        // Every bike matches, NOT EXISTS of bikes where condition is not TRUE (FALSE or unknown,
+        // i.e. compared to NULL color), people without bikes match (combine with bike_any(vec![])
+        // for at least one)
+        bike_all(Vec<bike::Condition>) => |conditions, options| {
+            let matching = match conditions.is_empty() {
+                // No conditions means no filter, as in create_filtered_query
+                true => CombineConditions::constant(true),
+                false => create_filter(conditions, AndOr::And, options)?,
+            };
+            let not_matching = bike::create_filtered_query_with(vec![], options)?
+                .filter(correlate(bike::bike::dsl::owner_id, person::dsl::id))
+                .filter(dsl::not(coalesce(matching, false)));
+            Box::new(dsl::not(dsl::exists(not_matching)).nullable())
+        },
+        // Correlated COUNT(*) subquery, built for every use of it in the filter, counts are integers
+        // so exclusive bounds of between are inclusive ones next to them, for a single BETWEEN
+        bike_count(RelationCount<bike::Condition>) => |relation, options| {
+            let RelationCount { conditions, count } = relation;
+            let count = match count {
+                NumberFilter::Between { from, to, from_inclusive, to_inclusive } => {
+                    NumberFilter::Between {
+                        from: if from_inclusive { from } else { from.saturating_add(1) },
+                        to: if to_inclusive { to } else { to.saturating_sub(1) },
+                        from_inclusive: true,
+                        to_inclusive: true,
+                    }
+                }
+                count => count,
+            };
+            let owned_bikes = || -> Result<_, FilterError> {
+                Ok(bike::create_filtered_count_with(conditions.clone(), options)?
+                    .filter(correlate(bike::bike::dsl::owner_id, person::dsl::id))
+                    .single_value())
+            };
+            number_filter!(count, owned_bikes()?)
+        },
     }
 }
 
-// This method can also be made into a macro, but it should be fine to just duplicate
-fn create_filter(conditions: Vec<Condition>, and_or: AndOr) -> Option<BoxedCondition> {
-    conditions
-        .into_iter()
-        // Map into array of boxed conditions
-        .filter_map::<BoxedCondition, _>(Condition::to_boxed_condition)
-        // Reduce to a boxed_condition1.and(boxed_condition2).and(boxed_condition3)...
-        .fold(None, |boxed_conditions, boxed_condition| {
-            Some(match boxed_conditions {
-                Some(bc) => match and_or {
-                    AndOr::And => Box::new(bc.and(boxed_condition)),
-                    AndOr::Or => Box::new(bc.or(boxed_condition)),
-                },
-                None => boxed_condition,
-            })
-        })
-}
-
-pub(super) fn create_filtered_query(conditions: Vec<Condition>) -> BoxedQuery {
-    let boxed_query = person::dsl::person.into_boxed();
-
-    match create_filter(conditions, AndOr::And) {
-        Some(boxed_conditions) => boxed_query.filter(boxed_conditions),
-        None => boxed_query,
+dynamic_sort! {
+    pub(super) Sort,
+    primary_key = [id],
+    fields {
+        id(String) => person::dsl::id,
+        name(String) => person::dsl::name,
     }
 }
@@ -0,0 +1,25 @@
+/*
+    Roads of bike trips, many to many through road_on_bike_trip (see bike_trip)
+*/
+
+use diesel::prelude::*;
+
+use crate::*;
+
+table! {
+    road (id) {
+        id -> Text,
+        name -> Text
+    }
+}
+
+dynamic_filter! {
+    pub(super) Condition,
+    Backend = diesel::sqlite::Sqlite,
+    ConditionSource = road::dsl::road,
+    QuerySource = road::dsl::road,
+    query = road::dsl::road,
+    fields {
+        name(StringFilter) => |f| string_filter!(f, road::dsl::name),
+    }
+}
@@ -0,0 +1,133 @@
+/*
+    Keyset (cursor) pagination, next page is filtered by sort key values of the last row rather
+    than an offset (see dynamic_sort! macro), cursor is opaque and URL safe, i.e. v6332.v6331
+*/
+
+use super::*;
+
+// Sort key values in cursor
+pub(crate) trait CursorValue: Sized {
+    fn to_cursor(&self) -> String;
+    fn from_cursor(value: &str) -> Option<Self>;
+}
+
+impl CursorValue for String {
+    fn to_cursor(&self) -> String {
+        self.clone()
+    }
+
+    fn from_cursor(value: &str) -> Option<Self> {
+        Some(value.to_string())
+    }
+}
+
+macro_rules! cursor_value_from_str {
+    ($($type:ty),*) => {
+        $(impl CursorValue for $type {
+            fn to_cursor(&self) -> String {
+                self.to_string()
+            }
+
+            fn from_cursor(value: &str) -> Option<Self> {
+                value.parse().ok()
+            }
+        })*
+    };
+}
+
+// Display of f64 is the shortest representation that parses back to the same value
+cursor_value_from_str!(i32, i64, f64, bool, NaiveDate, NaiveTime);
+
+const DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+impl CursorValue for NaiveDateTime {
+    fn to_cursor(&self) -> String {
+        self.format(DATE_TIME_FORMAT).to_string()
+    }
+
+    fn from_cursor(value: &str) -> Option<Self> {
+        NaiveDateTime::parse_from_str(value, DATE_TIME_FORMAT).ok()
+    }
+}
+
+// Values are separated by dots, n is NULL and v is followed by hex of the value
+pub(crate) fn encode_cursor(values: impl Iterator<Item = Option<String>>) -> String {
+    values
+        .map(|value| match value {
+            None => "n".to_string(),
+            Some(value) => value.bytes().fold("v".to_string(), |mut hex, byte| {
+                hex.push_str(&format!("{byte:02x}"));
+                hex
+            }),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+// Cursor of a different sort (number of keys) is invalid
+pub(crate) fn decode_cursor(cursor: &str, keys: usize) -> Result<Vec<Option<String>>, FilterError> {
+    let values = cursor
+        .split('.')
+        .map(|value| match value.strip_prefix('v') {
+            None if value == "n" => Some(None),
+            None => None,
+            Some(hex) if hex.is_ascii() && hex.len() % 2 == 0 => {
+                let bytes = (0..hex.len())
+                    .step_by(2)
+                    .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok())
+                    .collect::<Option<Vec<u8>>>()?;
+                String::from_utf8(bytes).ok().map(Some)
+            }
+            Some(_) => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or(FilterError::InvalidCursor)?;
+
+    match values.len() == keys {
+        true => Ok(values),
+        false => Err(FilterError::InvalidCursor),
+    }
+}
+
+pub(crate) fn decode_value<T: CursorValue>(
+    value: Option<String>,
+) -> Result<Option<T>, FilterError> {
+    value
+        .map(|value| T::from_cursor(&value).ok_or(FilterError::InvalidCursor))
+        .transpose()
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct KeysetPage<T> {
+    pub(crate) items: Vec<T>,
+    // Sort key values of the last item, when there are more rows
+    pub(crate) next_cursor: Option<String>,
+    pub(crate) has_more: bool,
+}
+
+#[test]
+fn test() {
+    let values = vec![Some("m2".to_string()), None, Some(String::new())];
+    let cursor = encode_cursor(values.clone().into_iter());
+
+    assert_eq!(cursor, "v6d32.n.v");
+    assert_eq!(decode_cursor(&cursor, 3), Ok(values));
+    assert_eq!(decode_cursor(&cursor, 2), Err(FilterError::InvalidCursor));
+    assert_eq!(decode_cursor("v6", 1), Err(FilterError::InvalidCursor));
+    assert_eq!(decode_cursor("vé0", 1), Err(FilterError::InvalidCursor));
+    assert_eq!(decode_cursor("x", 1), Err(FilterError::InvalidCursor));
+
+    assert_eq!(decode_value::<f64>(Some(0.1.to_cursor())), Ok(Some(0.1)));
+    assert_eq!(
+        decode_value::<i32>(Some("a".to_string())),
+        Err(FilterError::InvalidCursor)
+    );
+    let timestamp = NaiveDate::from_ymd_opt(2026, 10, 18)
+        .unwrap()
+        .and_hms_milli_opt(9, 30, 0, 5)
+        .unwrap();
+    assert_eq!(
+        decode_value(Some(timestamp.to_cursor())),
+        Ok(Some(timestamp))
+    );
+}
@@ -1,11 +1,61 @@
+#[cfg(any(feature = "postgres", feature = "mysql"))]
+mod backends;
+// Examples with SQLite tests
+#[cfg(feature = "sqlite")]
 mod dynamic_filters;
+mod evaluate;
+#[cfg(feature = "async-graphql")]
+mod graphql;
+#[cfg(feature = "sqlite")]
 mod inner_statement;
+mod keyset;
+mod syntax;
+
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+use diesel::{
+    backend::Backend,
+    dsl,
+    expression::{is_aggregate, AppearsOnTable, AsExpression, SqlLiteral, ValidGrouping},
+    helper_types::SqlTypeOf,
+    prelude::*,
+    query_builder::{AstPass, QueryFragment, QueryId},
+    sql_types::{Bool, Nullable, SingleValue, Text},
+};
+#[cfg(feature = "sqlite")]
+use evaluate::EvaluateCondition;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::ops::Bound;
+
 // Filters for "numbers"
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[derive(Debug, Clone)]
 enum NumberFilter<T> {
+    #[cfg_attr(feature = "serde", serde(rename = "eq"))]
     Equal(T),
+    #[cfg_attr(feature = "serde", serde(rename = "ne"))]
     NotEqual(T),
+    #[cfg_attr(feature = "serde", serde(rename = "gt"))]
     GreaterThen(T),
+    #[cfg_attr(feature = "serde", serde(rename = "lt"))]
     LowerThen(T),
+    #[cfg_attr(feature = "serde", serde(rename = "gte"))]
+    GreaterOrEqual(T),
+    #[cfg_attr(feature = "serde", serde(rename = "lte"))]
+    LowerOrEqual(T),
+    // Range, i.e. from a range slider, each bound can be inclusive or exclusive
+    Between {
+        from: T,
+        to: T,
+        from_inclusive: bool,
+        to_inclusive: bool,
+    },
+    In(Vec<T>),
+    NotIn(Vec<T>),
     IsNull,
     IsNotNull,
 }
@@ -17,17 +67,158 @@ macro_rules! number_filter {
             NumberFilter::NotEqual(value) => Box::new($dsl_field.ne(value).nullable()),
             NumberFilter::GreaterThen(value) => Box::new($dsl_field.gt(value).nullable()),
             NumberFilter::LowerThen(value) => Box::new($dsl_field.lt(value).nullable()),
+            NumberFilter::GreaterOrEqual(value) => Box::new($dsl_field.ge(value).nullable()),
+            NumberFilter::LowerOrEqual(value) => Box::new($dsl_field.le(value).nullable()),
+            NumberFilter::Between {
+                from,
+                to,
+                from_inclusive,
+                to_inclusive,
+            } => match (from_inclusive, to_inclusive) {
+                // BETWEEN has the field once, i.e. a correlated subquery runs once
+                (true, true) => Box::new($dsl_field.between(from, to).nullable()),
+                (true, false) => Box::new($dsl_field.ge(from).and($dsl_field.lt(to)).nullable()),
+                (false, true) => Box::new($dsl_field.gt(from).and($dsl_field.le(to)).nullable()),
+                (false, false) => Box::new($dsl_field.gt(from).and($dsl_field.lt(to)).nullable()),
+            },
+            NumberFilter::In(values) => Box::new($dsl_field.eq_any(values).nullable()),
+            NumberFilter::NotIn(values) => Box::new($dsl_field.ne_all(values).nullable()),
             NumberFilter::IsNull => Box::new($dsl_field.is_null().nullable()),
             NumberFilter::IsNotNull => Box::new($dsl_field.is_not_null().nullable()),
         }
     }};
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[derive(Debug, Clone)]
 enum StringFilter {
+    #[cfg_attr(feature = "serde", serde(rename = "eq"))]
     Equal(String),
+    #[cfg_attr(feature = "serde", serde(rename = "ne"))]
     NotEqual(String),
     Like(String),
+    NotLike(String),
     In(Vec<String>),
+    NotIn(Vec<String>),
+    // Wildcards (% and _) in value are matched literally, note that LIKE in SQLite is case
+    // insensitive for ASCII characters
+    StartsWith(String),
+    EndsWith(String),
+    Contains(String),
+    // * matches any characters and the rest of value is literal, i.e. RSQL name==a*b
+    Wildcard(String),
+    NotWildcard(String),
+    // Case insensitive, comparing lower() of both field and value
+    #[cfg_attr(feature = "serde", serde(rename = "eq_ignore_case"))]
+    EqualIgnoreCase(String),
+    ContainsIgnoreCase(String),
+    InIgnoreCase(Vec<String>),
+    IsNull,
+    IsNotNull,
+}
+
+sql_function!(fn lower<ST: SingleValue>(x: ST) -> ST);
+// coalesce(condition, FALSE) is FALSE when condition is unknown, to negate it as not matching
+sql_function!(fn coalesce(x: Nullable<Bool>, y: Bool) -> Bool);
+
+const LIKE_ESCAPE: char = '\\';
+
+// Escape LIKE wildcards and escape character itself, to be used with .escape(LIKE_ESCAPE)
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        if matches!(character, '%' | '_' | LIKE_ESCAPE) {
+            escaped.push(LIKE_ESCAPE);
+        }
+        escaped.push(character);
+    }
+    escaped
+}
+
+// LIKE pattern of Wildcard filter, to be used with .escape(LIKE_ESCAPE)
+fn wildcard_like(value: &str) -> String {
+    value
+        .split('*')
+        .map(escape_like)
+        .collect::<Vec<_>>()
+        .join("%")
+}
+
+// Column of the outer query in a correlated subquery, rendered as the column itself but allowed on
+// any query source, as a boxed subquery can't reference columns outside of its FROM clause
+#[derive(Debug, Clone, Copy, QueryId)]
+struct OuterColumn<C>(C);
+
+impl<C: Column> Expression for OuterColumn<C> {
+    type SqlType = C::SqlType;
+}
+
+impl<C: Column + QueryFragment<DB>, DB: Backend> QueryFragment<DB> for OuterColumn<C> {
+    fn walk_ast<'b>(&'b self, pass: AstPass<'_, 'b, DB>) -> QueryResult<()> {
+        self.0.walk_ast(pass)
+    }
+}
+
+impl<C: Column, QS> AppearsOnTable<QS> for OuterColumn<C> {}
+
+// Outer column is a single value for every row of the subquery
+impl<C> ValidGrouping<()> for OuterColumn<C> {
+    type IsAggregate = is_aggregate::Never;
+}
+
+// inner = outer correlation of a subquery, with both sides from diesel's table and column names
+// (nullable, as either may be a nullable foreign key)
+fn correlate<Inner, Outer>(
+    inner: Inner,
+    outer: Outer,
+) -> dsl::Eq<dsl::Nullable<Inner>, dsl::Nullable<OuterColumn<Outer>>>
+where
+    Inner: Column,
+    Outer: Column,
+    dsl::Nullable<Inner>: ExpressionMethods,
+    SqlTypeOf<dsl::Nullable<Inner>>: diesel::sql_types::SqlType,
+    dsl::Nullable<OuterColumn<Outer>>: AsExpression<
+        SqlTypeOf<dsl::Nullable<Inner>>,
+        Expression = dsl::Nullable<OuterColumn<Outer>>,
+    >,
+{
+    inner.nullable().eq(OuterColumn(outer).nullable())
+}
+
+// Text column boxed for backend specific operators (with bounds that are implied for a concrete
+// backend, as in CombineConditions), comparisons of nullable text are already Nullable<Bool>
+type BoxedText<QS, DB> = Box<dyn BoxableExpression<QS, DB, SqlType = Nullable<Text>>>;
+
+// Case insensitive LIKE, ILIKE where available and lower() of both sides otherwise
+trait ContainsIgnoreCase<QS, DB> {
+    fn contains_ignore_case(self, pattern: String) -> BoxedCondition<QS, DB>;
+}
+
+#[cfg(any(feature = "sqlite", feature = "mysql"))]
+macro_rules! lower_like {
+    ($backend:ty) => {
+        impl<QS: 'static> ContainsIgnoreCase<QS, $backend> for BoxedText<QS, $backend> {
+            fn contains_ignore_case(self, pattern: String) -> BoxedCondition<QS, $backend> {
+                Box::new(lower(self).like(lower(pattern)).escape(LIKE_ESCAPE))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "sqlite")]
+lower_like!(diesel::sqlite::Sqlite);
+#[cfg(feature = "mysql")]
+lower_like!(diesel::mysql::Mysql);
+
+#[cfg(feature = "postgres")]
+impl<QS: 'static> ContainsIgnoreCase<QS, diesel::pg::Pg> for BoxedText<QS, diesel::pg::Pg> {
+    fn contains_ignore_case(self, pattern: String) -> BoxedCondition<QS, diesel::pg::Pg> {
+        Box::new(self.ilike(pattern).escape(LIKE_ESCAPE))
+    }
 }
 
 macro_rules! string_filter {
@@ -36,11 +227,58 @@ macro_rules! string_filter {
             StringFilter::Equal(value) => Box::new($dsl_field.eq(value).nullable()),
             StringFilter::NotEqual(value) => Box::new($dsl_field.ne(value).nullable()),
             StringFilter::Like(value) => Box::new($dsl_field.like(value).nullable()),
+            StringFilter::NotLike(value) => Box::new($dsl_field.not_like(value).nullable()),
             StringFilter::In(value) => Box::new($dsl_field.eq_any(value).nullable()),
+            StringFilter::NotIn(value) => Box::new($dsl_field.ne_all(value).nullable()),
+            StringFilter::StartsWith(value) => {
+                let pattern = format!("{}%", escape_like(&value));
+                Box::new($dsl_field.like(pattern).escape(LIKE_ESCAPE).nullable())
+            }
+            StringFilter::EndsWith(value) => {
+                let pattern = format!("%{}", escape_like(&value));
+                Box::new($dsl_field.like(pattern).escape(LIKE_ESCAPE).nullable())
+            }
+            StringFilter::Contains(value) => {
+                let pattern = format!("%{}%", escape_like(&value));
+                Box::new($dsl_field.like(pattern).escape(LIKE_ESCAPE).nullable())
+            }
+            StringFilter::Wildcard(value) => {
+                let pattern = wildcard_like(&value);
+                Box::new($dsl_field.like(pattern).escape(LIKE_ESCAPE).nullable())
+            }
+            StringFilter::NotWildcard(value) => {
+                let pattern = wildcard_like(&value);
+                Box::new($dsl_field.not_like(pattern).escape(LIKE_ESCAPE).nullable())
+            }
+            StringFilter::EqualIgnoreCase(value) => {
+                Box::new(lower($dsl_field).eq(lower(value)).nullable())
+            }
+            StringFilter::ContainsIgnoreCase(value) => {
+                let pattern = format!("%{}%", escape_like(&value));
+                let field: BoxedText<_, _> = Box::new($dsl_field.nullable());
+                field.contains_ignore_case(pattern)
+            }
+            // lower(field) = lower(value1) OR lower(field) = lower(value2) ..., rather than
+            // lowering values in rust, to match lower() of the database
+            StringFilter::InIgnoreCase(values) => values
+                .into_iter()
+                .map(|value| -> BoxedCondition {
+                    Box::new(lower($dsl_field).eq(lower(value)).nullable())
+                })
+                .reduce(|in_values, value| in_values.combine(value, &AndOr::Or))
+                .unwrap_or_else(|| CombineConditions::constant(false)),
+            StringFilter::IsNull => Box::new($dsl_field.is_null().nullable()),
+            StringFilter::IsNotNull => Box::new($dsl_field.is_not_null().nullable()),
         }
     }};
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[derive(Debug, Clone)]
 enum BooleanFilter {
     True,
     False,
@@ -59,11 +297,1109 @@ macro_rules! boolean_filter {
     }};
 }
 
+// Filters for dates, times and timestamps (chrono NaiveDate, NaiveTime and NaiveDateTime)
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[derive(Debug, Clone)]
+enum DateTimeFilter<T> {
+    #[cfg_attr(feature = "serde", serde(rename = "eq"))]
+    Equal(T),
+    Before(T),
+    After(T),
+    // Inclusive
+    Between {
+        from: T,
+        to: T,
+    },
+    OnDay(NaiveDate),
+    // Relative to FilterOptions clock: last N units (up to now), older than N units and
+    // calendar period containing now (i.e. this month)
+    Last(u32, TimeUnit),
+    OlderThan(u32, TimeUnit),
+    This(Period),
+    IsNull,
+    IsNotNull,
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[cfg_attr(feature = "async-graphql", derive(async_graphql::Enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimeUnit {
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[cfg_attr(feature = "async-graphql", derive(async_graphql::Enum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Period {
+    Day,
+    // Starting on Monday
+    Week,
+    Month,
+    Year,
+}
+
+// Relative and calendar filters are resolved to a range when condition is boxed
+enum ResolvedDateTimeFilter<T> {
+    Equal(T),
+    Range(Bound<T>, Bound<T>),
+    IsNull,
+    IsNotNull,
+}
+
+impl<T: Temporal> DateTimeFilter<T> {
+    fn resolve(self, now: NaiveDateTime) -> Result<ResolvedDateTimeFilter<T>, FilterError> {
+        use Bound::*;
+        let midnight = |date: NaiveDate| date.and_time(NaiveTime::MIN);
+
+        let (from, to) = match self {
+            DateTimeFilter::Equal(value) => return Ok(ResolvedDateTimeFilter::Equal(value)),
+            DateTimeFilter::IsNull => return Ok(ResolvedDateTimeFilter::IsNull),
+            DateTimeFilter::IsNotNull => return Ok(ResolvedDateTimeFilter::IsNotNull),
+            DateTimeFilter::Before(value) => {
+                return Ok(ResolvedDateTimeFilter::Range(Unbounded, Excluded(value)))
+            }
+            DateTimeFilter::After(value) => {
+                return Ok(ResolvedDateTimeFilter::Range(Excluded(value), Unbounded))
+            }
+            DateTimeFilter::Between { from, to } => {
+                return Ok(ResolvedDateTimeFilter::Range(Included(from), Included(to)))
+            }
+            DateTimeFilter::OnDay(day) => (
+                Included(midnight(day)),
+                Excluded(midnight(
+                    day.succ_opt().ok_or(FilterError::InvalidDateTimeRange)?,
+                )),
+            ),
+            DateTimeFilter::Last(number, unit) => {
+                (Included(unit.before(now, number)?), Included(now))
+            }
+            DateTimeFilter::OlderThan(number, unit) => {
+                (Unbounded, Excluded(unit.before(now, number)?))
+            }
+            DateTimeFilter::This(period) => {
+                let today = now.date();
+                let (start, end) = match period {
+                    Period::Day => (Some(today), today.succ_opt()),
+                    Period::Week => {
+                        let start =
+                            today - Days::new(today.weekday().num_days_from_monday().into());
+                        (Some(start), start.checked_add_days(Days::new(7)))
+                    }
+                    Period::Month => {
+                        let start = today.with_day(1);
+                        (
+                            start,
+                            start.and_then(|start| start.checked_add_months(Months::new(1))),
+                        )
+                    }
+                    Period::Year => {
+                        let start = NaiveDate::from_ymd_opt(today.year(), 1, 1);
+                        (start, NaiveDate::from_ymd_opt(today.year() + 1, 1, 1))
+                    }
+                };
+                match (start, end) {
+                    (Some(start), Some(end)) => {
+                        (Included(midnight(start)), Excluded(midnight(end)))
+                    }
+                    _ => return Err(FilterError::InvalidDateTimeRange),
+                }
+            }
+        };
+
+        let (from, to) = T::from_range(from, to)?;
+        Ok(ResolvedDateTimeFilter::Range(from, to))
+    }
+}
+
+impl TimeUnit {
+    fn before(&self, now: NaiveDateTime, number: u32) -> Result<NaiveDateTime, FilterError> {
+        let number = i64::from(number);
+        let delta = match self {
+            TimeUnit::Minutes => TimeDelta::try_minutes(number),
+            TimeUnit::Hours => TimeDelta::try_hours(number),
+            TimeUnit::Days => TimeDelta::try_days(number),
+            TimeUnit::Weeks => TimeDelta::try_weeks(number),
+        };
+
+        delta
+            .and_then(|delta| now.checked_sub_signed(delta))
+            .ok_or(FilterError::InvalidDateTimeRange)
+    }
+}
+
+// Column types that relative and calendar date filters can be resolved to
+trait Temporal: Sized {
+    fn from_range(
+        from: Bound<NaiveDateTime>,
+        to: Bound<NaiveDateTime>,
+    ) -> Result<(Bound<Self>, Bound<Self>), FilterError>;
+}
+
+impl Temporal for NaiveDateTime {
+    fn from_range(
+        from: Bound<NaiveDateTime>,
+        to: Bound<NaiveDateTime>,
+    ) -> Result<(Bound<Self>, Bound<Self>), FilterError> {
+        Ok((from, to))
+    }
+}
+
+// Truncated to whole days, i.e. older than 48 hours is before the day of (now - 48 hours)
+impl Temporal for NaiveDate {
+    fn from_range(
+        from: Bound<NaiveDateTime>,
+        to: Bound<NaiveDateTime>,
+    ) -> Result<(Bound<Self>, Bound<Self>), FilterError> {
+        Ok((from.map(|from| from.date()), to.map(|to| to.date())))
+    }
+}
+
+// Time of day, range must be within one day (i.e. last 2 hours at 1am is not supported)
+impl Temporal for NaiveTime {
+    fn from_range(
+        from: Bound<NaiveDateTime>,
+        to: Bound<NaiveDateTime>,
+    ) -> Result<(Bound<Self>, Bound<Self>), FilterError> {
+        match (&from, &to) {
+            (
+                Bound::Included(from_value) | Bound::Excluded(from_value),
+                Bound::Included(to_value) | Bound::Excluded(to_value),
+            ) if from_value.date() == to_value.date() => {
+                Ok((from.map(|from| from.time()), to.map(|to| to.time())))
+            }
+            _ => Err(FilterError::InvalidDateTimeRange),
+        }
+    }
+}
+
+// Options need to be in scope, to resolve relative filters with it's now
+macro_rules! date_filter {
+    ($filter:ident, $dsl_field:expr, $options:ident ) => {{
+        match $filter.resolve($options.now())? {
+            ResolvedDateTimeFilter::Equal(value) => Box::new($dsl_field.eq(value).nullable()),
+            ResolvedDateTimeFilter::IsNull => Box::new($dsl_field.is_null().nullable()),
+            ResolvedDateTimeFilter::IsNotNull => Box::new($dsl_field.is_not_null().nullable()),
+            ResolvedDateTimeFilter::Range(from, to) => {
+                let from: Option<BoxedCondition> = match from {
+                    std::ops::Bound::Included(value) => {
+                        Some(Box::new($dsl_field.ge(value).nullable()))
+                    }
+                    std::ops::Bound::Excluded(value) => {
+                        Some(Box::new($dsl_field.gt(value).nullable()))
+                    }
+                    std::ops::Bound::Unbounded => None,
+                };
+                let to: Option<BoxedCondition> = match to {
+                    std::ops::Bound::Included(value) => {
+                        Some(Box::new($dsl_field.le(value).nullable()))
+                    }
+                    std::ops::Bound::Excluded(value) => {
+                        Some(Box::new($dsl_field.lt(value).nullable()))
+                    }
+                    std::ops::Bound::Unbounded => None,
+                };
+                match (from, to) {
+                    (Some(from), Some(to)) => Box::new(from.and(to)),
+                    (Some(condition), None) | (None, Some(condition)) => condition,
+                    (None, None) => CombineConditions::constant(true),
+                }
+            }
+        }
+    }};
+}
+
 enum AndOr {
     And,
     Or,
 }
 
+// How And/Or groups without conditions are treated
+#[derive(Debug, Clone, Copy, Default)]
+enum EmptyGroup {
+    // Empty And is TRUE and empty Or is FALSE (as in maths, i.e. deselecting every option in a
+    // multi-select Or group matches nothing)
+    #[default]
+    Identity,
+    // Return FilterError::EmptyGroup
+    Reject,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FilterOptions {
+    empty_group: EmptyGroup,
+    // Now, for relative date filters (injectable for tests)
+    clock: fn() -> NaiveDateTime,
+    // Fixed now instead of clock, for queries that have to resolve filters the same way (count and
+    // items of a page)
+    now: Option<NaiveDateTime>,
+}
+
+impl Default for FilterOptions {
+    fn default() -> Self {
+        FilterOptions {
+            empty_group: EmptyGroup::default(),
+            clock: || Utc::now().naive_utc(),
+            now: None,
+        }
+    }
+}
+
+impl FilterOptions {
+    fn now(&self) -> NaiveDateTime {
+        self.now.unwrap_or_else(self.clock)
+    }
+
+    // Same options with now read from the clock once
+    fn with_fixed_now(&self) -> Self {
+        FilterOptions {
+            now: Some(self.now()),
+            ..*self
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum FilterError {
+    EmptyGroup,
+    // Relative or calendar date filter cannot be resolved for the column, or is out of range
+    InvalidDateTimeRange,
+    // Keyset cursor cannot be decoded, or is for a different sort
+    InvalidCursor,
+}
+
+// Offset page with total count of the filter (see load_page in dynamic_filter! macro)
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[derive(Debug, Clone, PartialEq)]
+struct Page<T> {
+    items: Vec<T>,
+    total: i64,
+    limit: i64,
+    offset: i64,
+}
+
+#[derive(Debug)]
+enum PageError {
+    Filter(FilterError),
+    Query(diesel::result::Error),
+}
+
+impl From<FilterError> for PageError {
+    fn from(error: FilterError) -> Self {
+        PageError::Filter(error)
+    }
+}
+
+impl From<diesel::result::Error> for PageError {
+    fn from(error: diesel::result::Error) -> Self {
+        PageError::Query(error)
+    }
+}
+
+// Number of related rows matching inner statement conditions, i.e. persons with two or more orange
+// bikes {"conditions": [{"color": {"eq": "orange"}}], "count": {"gte": 2}}
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(bound(deserialize = "C: Deserialize<'de>"))
+)]
+// Doc tests include the crate (its items are private), RelationCount compiles for a table without
+// count field and doesn't for a table with it
+#[cfg_attr(
+    feature = "sqlite",
+    doc = r#"
+```
+include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/lib.rs"));
+
+table! {
+    item (id) {
+        id -> Text,
+        quantity -> Integer,
+    }
+}
+
+table! {
+    shop (id) {
+        id -> Text,
+    }
+}
+
+mod items {
+    use super::*;
+
+    dynamic_filter! {
+        pub(super) Condition,
+        Backend = diesel::sqlite::Sqlite,
+        ConditionSource = item::dsl::item,
+        QuerySource = item::dsl::item,
+        query = item::dsl::item,
+        fields {
+            quantity(NumberFilter<i32>) => |f| number_filter!(f, item::dsl::quantity),
+        }
+    }
+}
+
+mod shops {
+    use super::*;
+
+    dynamic_filter! {
+        pub(super) Condition,
+        Backend = diesel::sqlite::Sqlite,
+        ConditionSource = shop::dsl::shop,
+        QuerySource = shop::dsl::shop,
+        query = shop::dsl::shop,
+        fields {
+            item_count(RelationCount<items::Condition>) => |relation| {
+                Box::new(dsl::sql::<Bool>("TRUE").nullable())
+            },
+        }
+    }
+}
+
+fn main() {
+    let _ = syntax::text::parse::<shops::Field>("item_count(count >= 2)");
+}
+```
+
+```compile_fail,E0080
+include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/lib.rs"));
+
+table! {
+    item (id) {
+        id -> Text,
+        count -> Integer,
+    }
+}
+
+table! {
+    shop (id) {
+        id -> Text,
+    }
+}
+
+mod items {
+    use super::*;
+
+    dynamic_filter! {
+        pub(super) Condition,
+        Backend = diesel::sqlite::Sqlite,
+        ConditionSource = item::dsl::item,
+        QuerySource = item::dsl::item,
+        query = item::dsl::item,
+        fields {
+            count(NumberFilter<i32>) => |f| number_filter!(f, item::dsl::count),
+        }
+    }
+}
+
+mod shops {
+    use super::*;
+
+    dynamic_filter! {
+        pub(super) Condition,
+        Backend = diesel::sqlite::Sqlite,
+        ConditionSource = shop::dsl::shop,
+        QuerySource = shop::dsl::shop,
+        query = shop::dsl::shop,
+        fields {
+            item_count(RelationCount<items::Condition>) => |relation| {
+                Box::new(dsl::sql::<Bool>("TRUE").nullable())
+            },
+        }
+    }
+}
+
+fn main() {
+    let _ = syntax::text::parse::<shops::Field>("item_count(count >= 2)");
+}
+```
+"#
+)]
+#[derive(Debug, Clone)]
+struct RelationCount<C> {
+    #[cfg_attr(feature = "serde", serde(default))]
+    conditions: Vec<C>,
+    count: NumberFilter<i64>,
+}
+
+// Need this type for common condition expressions
+type BoxedCondition<QS, DB> = Box<dyn BoxableExpression<QS, DB, SqlType = Nullable<Bool>>>;
+
+// Implemented by every table's field enum (see dynamic_filter! macro) and by ConditionTree
+trait DynamicCondition: Sized {
+    type Source: 'static;
+    type Backend: Backend + 'static;
+
+    #[allow(clippy::wrong_self_convention)]
+    fn to_boxed_condition(
+        self,
+        options: &FilterOptions,
+    ) -> Result<BoxedCondition<Self::Source, Self::Backend>, FilterError>;
+}
+
+// And/Or combinators are shared by all tables, each table only declares it's fields (F)
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[derive(Debug, Clone, PartialEq)]
+enum ConditionTree<F> {
+    And(Vec<ConditionTree<F>>),
+    Or(Vec<ConditionTree<F>>),
+    Not(Box<ConditionTree<F>>),
+    True,
+    False,
+    // Field is not wrapped in JSON, i.e. {"and": [{"number_field": {"gt": 1}}]}
+    #[cfg_attr(feature = "serde", serde(untagged))]
+    Field(F),
+}
+
+impl<F> DynamicCondition for ConditionTree<F>
+where
+    F: DynamicCondition,
+    BoxedCondition<F::Source, F::Backend>: CombineConditions,
+{
+    type Source = F::Source;
+    type Backend = F::Backend;
+
+    fn to_boxed_condition(
+        self,
+        options: &FilterOptions,
+    ) -> Result<BoxedCondition<Self::Source, Self::Backend>, FilterError> {
+        Ok(match self {
+            ConditionTree::Field(field) => field.to_boxed_condition(options)?,
+            ConditionTree::And(conditions) => create_filter(conditions, AndOr::And, options)?,
+            ConditionTree::Or(conditions) => create_filter(conditions, AndOr::Or, options)?,
+            // NOT NULL is NULL, so rows where inner condition is unknown (i.e. compared to null
+            // from left join) are excluded by both condition and it's negation
+            ConditionTree::Not(condition) => condition.to_boxed_condition(options)?.negate(),
+            ConditionTree::True => CombineConditions::constant(true),
+            ConditionTree::False => CombineConditions::constant(false),
+        })
+    }
+}
+
+// Combining boxed conditions needs bounds that diesel puts on And, Or and Not for a backend, they
+// are implied for concrete backends but can't be named for a generic one, thus this helper trait
+trait CombineConditions {
+    fn combine(self, other: Self, and_or: &AndOr) -> Self;
+    fn negate(self) -> Self;
+    fn constant(value: bool) -> Self;
+}
+
+impl<QS, DB> CombineConditions for BoxedCondition<QS, DB>
+where
+    QS: 'static,
+    DB: Backend + 'static,
+    dsl::And<Self, Self, Nullable<Bool>>: QueryFragment<DB>,
+    dsl::Or<Self, Self, Nullable<Bool>>: QueryFragment<DB>,
+    dsl::not<Self>: QueryFragment<DB>,
+    SqlLiteral<Nullable<Bool>>: QueryFragment<DB>,
+{
+    fn combine(self, other: Self, and_or: &AndOr) -> Self {
+        match and_or {
+            AndOr::And => Box::new(self.and(other)),
+            AndOr::Or => Box::new(self.or(other)),
+        }
+    }
+
+    fn negate(self) -> Self {
+        Box::new(dsl::not(self))
+    }
+
+    fn constant(value: bool) -> Self {
+        Box::new(dsl::sql::<Nullable<Bool>>(if value {
+            "TRUE"
+        } else {
+            "FALSE"
+        }))
+    }
+}
+
+fn create_filter<C>(
+    conditions: Vec<C>,
+    and_or: AndOr,
+    options: &FilterOptions,
+) -> Result<BoxedCondition<C::Source, C::Backend>, FilterError>
+where
+    C: DynamicCondition,
+    BoxedCondition<C::Source, C::Backend>: CombineConditions,
+{
+    if conditions.is_empty() {
+        return match (options.empty_group, and_or) {
+            (EmptyGroup::Reject, _) => Err(FilterError::EmptyGroup),
+            (EmptyGroup::Identity, AndOr::And) => Ok(CombineConditions::constant(true)),
+            (EmptyGroup::Identity, AndOr::Or) => Ok(CombineConditions::constant(false)),
+        };
+    }
+
+    conditions
+        .into_iter()
+        // Map into array of boxed conditions
+        .map(|condition| condition.to_boxed_condition(options))
+        // Reduce to a boxed_condition1.and(boxed_condition2).and(boxed_condition3)...
+        .reduce(|boxed_conditions, boxed_condition| {
+            Ok(boxed_conditions?.combine(boxed_condition?, &and_or))
+        })
+        // Not empty, checked above
+        .unwrap()
+}
+
+macro_rules! dynamic_filter {
+    (
+        $vis:vis Condition,
+        Backend = $backend:ty,
+        ConditionSource = $condition_source:ty,
+        QuerySource = $query_source:ty,
+        query = $query:expr,
+        fields {
+            $(
+                $field:ident($filter:ty) => |$value:ident $(, $options:ident)?| $to_boxed:expr
+            ),* $(,)?
+        }
+    ) => {
+        #[allow(non_camel_case_types)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[derive(Debug, Clone)]
+        $vis enum Field {
+            $($field($filter),)*
+        }
+
+        $vis type Condition = $crate::ConditionTree<Field>;
+
+        type ConditionSource = $condition_source;
+        type Backend = $backend;
+        type BoxedCondition = $crate::BoxedCondition<ConditionSource, Backend>;
+        type QuerySource = $query_source;
+        type BoxedQuery = diesel::helper_types::IntoBoxed<'static, QuerySource, Backend>;
+
+        impl $crate::DynamicCondition for Field {
+            type Source = ConditionSource;
+            type Backend = Backend;
+
+            #[allow(unused_variables)]
+            fn to_boxed_condition(
+                self,
+                options: &$crate::FilterOptions,
+            ) -> Result<BoxedCondition, $crate::FilterError> {
+                Ok(match self {
+                    $(Field::$field($value) => {
+                        $(let $options = options;)?
+                        $to_boxed
+                    })*
+                })
+            }
+        }
+
+        impl $crate::syntax::FieldRegistry for Field {
+            const FIELDS: &'static [&'static str] = &[$(stringify!($field)),*];
+
+            fn from_field_condition(
+                condition: $crate::syntax::FieldCondition,
+            ) -> Result<Self, $crate::syntax::SyntaxError> {
+                use $crate::syntax::FieldSyntax;
+
+                let $crate::syntax::FieldCondition { field, location, filter } = condition;
+                match field.as_str() {
+                    $(stringify!($field) => Ok(Field::$field(
+                        <$filter>::from_field_filter(filter, &field, location)?
+                    )),)*
+                    _ => Err($crate::syntax::SyntaxError::at(
+                        location,
+                        $crate::syntax::SyntaxErrorKind::UnknownField(field),
+                    )),
+                }
+            }
+
+            fn to_field_condition(&self) -> $crate::syntax::FieldCondition {
+                use $crate::syntax::FieldSyntax;
+
+                match self {
+                    $(Field::$field(value) => $crate::syntax::FieldCondition {
+                        field: stringify!($field).to_string(),
+                        location: None,
+                        filter: value.to_field_filter(),
+                    },)*
+                }
+            }
+        }
+
+        #[cfg(feature = "async-graphql")]
+        #[derive(async_graphql::InputObject)]
+        #[graphql(name_type)]
+        $vis struct ConditionInput {
+            and: Option<Vec<ConditionInput>>,
+            or: Option<Vec<ConditionInput>>,
+            not: Option<Box<ConditionInput>>,
+            $($field: Option<<$filter as $crate::graphql::GraphqlFilter>::Input>,)*
+        }
+
+        // Type name from module, i.e. PersonConditionInput
+        #[cfg(feature = "async-graphql")]
+        impl async_graphql::TypeName for ConditionInput {
+            fn type_name() -> std::borrow::Cow<'static, str> {
+                $crate::graphql::type_name(module_path!(), "ConditionInput").into()
+            }
+        }
+
+        // Every key that is set is a condition of And group
+        #[cfg(feature = "async-graphql")]
+        impl From<ConditionInput> for Condition {
+            fn from(input: ConditionInput) -> Self {
+                use $crate::graphql::GraphqlFilter;
+
+                let mut conditions = Vec::new();
+                if let Some(inputs) = input.and {
+                    conditions.push($crate::ConditionTree::And(
+                        inputs.into_iter().map(Condition::from).collect(),
+                    ));
+                }
+                if let Some(inputs) = input.or {
+                    conditions.push($crate::ConditionTree::Or(
+                        inputs.into_iter().map(Condition::from).collect(),
+                    ));
+                }
+                if let Some(input) = input.not {
+                    conditions.push($crate::ConditionTree::Not(Box::new(Condition::from(*input))));
+                }
+                $(if let Some(input) = input.$field {
+                    conditions.push(Condition::$field(<$filter>::from_input(input)));
+                })*
+
+                match conditions.len() {
+                    1 => conditions.pop().unwrap(),
+                    _ => $crate::ConditionTree::And(conditions),
+                }
+            }
+        }
+
+        // Inner statement conditions of this table and their count, i.e. BikeCountInput
+        #[cfg(feature = "async-graphql")]
+        #[derive(async_graphql::InputObject)]
+        #[graphql(name_type)]
+        $vis struct CountInput {
+            conditions: Option<ConditionInput>,
+            count: <$crate::NumberFilter<i64> as $crate::graphql::GraphqlFilter>::Input,
+        }
+
+        #[cfg(feature = "async-graphql")]
+        impl async_graphql::TypeName for CountInput {
+            fn type_name() -> std::borrow::Cow<'static, str> {
+                $crate::graphql::type_name(module_path!(), "CountInput").into()
+            }
+        }
+
+        #[cfg(feature = "async-graphql")]
+        impl From<CountInput> for $crate::RelationCount<Condition> {
+            fn from(input: CountInput) -> Self {
+                use $crate::graphql::GraphqlFilter;
+
+                $crate::RelationCount {
+                    conditions: input.conditions.map(Condition::from).into_iter().collect(),
+                    count: $crate::NumberFilter::from_input(input.count),
+                }
+            }
+        }
+
+        #[cfg(feature = "async-graphql")]
+        impl $crate::graphql::GraphqlFields for Field {
+            type Input = ConditionInput;
+            type CountInput = CountInput;
+        }
+
+        impl Condition {
+            $($vis fn $field(value: $filter) -> Self {
+                $crate::ConditionTree::Field(Field::$field(value))
+            })*
+        }
+
+        $vis fn create_filtered_query(
+            conditions: Vec<Condition>,
+        ) -> Result<BoxedQuery, $crate::FilterError> {
+            create_filtered_query_with(conditions, &$crate::FilterOptions::default())
+        }
+
+        $vis fn create_filtered_query_with(
+            conditions: Vec<Condition>,
+            options: &$crate::FilterOptions,
+        ) -> Result<BoxedQuery, $crate::FilterError> {
+            let boxed_query = $query.into_boxed();
+
+            // No conditions at the top level means no filter (rather then an empty And group)
+            if conditions.is_empty() {
+                return Ok(boxed_query);
+            }
+
+            Ok(boxed_query.filter($crate::create_filter(conditions, $crate::AndOr::And, options)?))
+        }
+
+        // COUNT(*) of the same filtered query (rows of joins, as in pages)
+        $vis fn create_filtered_count(
+            conditions: Vec<Condition>,
+        ) -> Result<diesel::dsl::Select<BoxedQuery, diesel::dsl::CountStar>, $crate::FilterError> {
+            create_filtered_count_with(conditions, &$crate::FilterOptions::default())
+        }
+
+        $vis fn create_filtered_count_with(
+            conditions: Vec<Condition>,
+            options: &$crate::FilterOptions,
+        ) -> Result<diesel::dsl::Select<BoxedQuery, diesel::dsl::CountStar>, $crate::FilterError> {
+            Ok(create_filtered_query_with(conditions, options)?.select(diesel::dsl::count_star()))
+        }
+
+        // Offset page and total count of conditions, select (and order) items in `select`
+        $vis fn load_page<C, T, Q>(
+            connection: &mut C,
+            conditions: Vec<Condition>,
+            limit: i64,
+            offset: i64,
+            select: impl FnOnce(BoxedQuery) -> Q,
+        ) -> Result<$crate::Page<T>, $crate::PageError>
+        where
+            C: diesel::Connection<Backend = Backend>,
+            diesel::dsl::Select<BoxedQuery, diesel::dsl::CountStar>:
+                diesel::query_dsl::LoadQuery<'static, C, i64>,
+            Q: diesel::RunQueryDsl<C> + diesel::query_dsl::LoadQuery<'static, C, T>,
+        {
+            let options = $crate::FilterOptions::default();
+            load_page_with(connection, conditions, &options, limit, offset, select)
+        }
+
+        // Relative date filters of count and items are resolved with the same now
+        $vis fn load_page_with<C, T, Q>(
+            connection: &mut C,
+            conditions: Vec<Condition>,
+            options: &$crate::FilterOptions,
+            limit: i64,
+            offset: i64,
+            select: impl FnOnce(BoxedQuery) -> Q,
+        ) -> Result<$crate::Page<T>, $crate::PageError>
+        where
+            C: diesel::Connection<Backend = Backend>,
+            diesel::dsl::Select<BoxedQuery, diesel::dsl::CountStar>:
+                diesel::query_dsl::LoadQuery<'static, C, i64>,
+            Q: diesel::RunQueryDsl<C> + diesel::query_dsl::LoadQuery<'static, C, T>,
+        {
+            let options = options.with_fixed_now();
+            let total =
+                create_filtered_count_with(conditions.clone(), &options)?.get_result(connection)?;
+            let query = create_filtered_query_with(conditions, &options)?
+                .limit(limit)
+                .offset(offset);
+
+            Ok($crate::Page {
+                items: select(query).load(connection)?,
+                total,
+                limit,
+                offset,
+            })
+        }
+    };
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NullsOrder {
+    First,
+    Last,
+}
+
+// Sort key of a table's sort field enum (see dynamic_sort! macro), i.e.
+// {"field": "color", "direction": "desc", "nulls": "last"}
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SortKey<F> {
+    field: F,
+    #[cfg_attr(feature = "serde", serde(default))]
+    direction: SortDirection,
+    // First when ascending and last when descending if not set (as if NULL was smallest, SQLite's
+    // order), on every backend
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    nulls: Option<NullsOrder>,
+}
+
+impl<F> SortKey<F> {
+    fn asc(field: F) -> Self {
+        SortKey {
+            field,
+            direction: SortDirection::Asc,
+            nulls: None,
+        }
+    }
+
+    fn desc(field: F) -> Self {
+        SortKey {
+            field,
+            direction: SortDirection::Desc,
+            nulls: None,
+        }
+    }
+
+    fn nulls(self, nulls: NullsOrder) -> Self {
+        SortKey {
+            nulls: Some(nulls),
+            ..self
+        }
+    }
+
+    // Placement of NULLs for both ORDER BY (sort_by!) and keyset condition (keyset_key!), which
+    // have to agree, so it's explicit in ORDER BY rather than left to the backend (PostgreSQL puts
+    // NULL last when ascending)
+    fn nulls_last(&self) -> bool {
+        match self.nulls {
+            Some(NullsOrder::First) => false,
+            Some(NullsOrder::Last) => true,
+            None => self.direction == SortDirection::Desc,
+        }
+    }
+}
+
+// Filter and sort travelling together in one request, i.e. {"filter": [..], "sort": [..]}
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(bound(deserialize = "C: Deserialize<'de>, S: Deserialize<'de>"))
+)]
+#[derive(Debug, Clone, PartialEq)]
+struct ListRequest<C, S> {
+    #[cfg_attr(feature = "serde", serde(default))]
+    filter: Vec<C>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    sort: Vec<S>,
+}
+
+// NULLS FIRST/LAST is not available for every backend in diesel, ordering by IS NULL instead
+macro_rules! sort_by {
+    ($query:ident, $sort:ident, $dsl_field:expr ) => {{
+        let $query = match $sort.nulls_last() {
+            true => $query.then_order_by($dsl_field.is_null().asc()),
+            false => $query.then_order_by($dsl_field.is_null().desc()),
+        };
+        match $sort.direction {
+            $crate::SortDirection::Asc => $query.then_order_by($dsl_field.asc()),
+            $crate::SortDirection::Desc => $query.then_order_by($dsl_field.desc()),
+        }
+    }};
+}
+
+/*
+    Keyset condition parts for a sort key and cursor value, rows equal to value and rows after it
+    in sort order (NULLs are placed as in sort_by!)
+*/
+macro_rules! keyset_key {
+    ($sort:ident, $column:path, $value:ident) => {{
+        let nulls_last = $sort.nulls_last();
+        match $value {
+            None => (
+                Box::new($column.is_null().nullable()) as BoxedCondition,
+                match nulls_last {
+                    true => $crate::CombineConditions::constant(false),
+                    false => Box::new($column.is_not_null().nullable()) as BoxedCondition,
+                },
+            ),
+            Some(value) => {
+                let after: BoxedCondition = match $sort.direction {
+                    $crate::SortDirection::Asc => Box::new($column.gt(value.clone()).nullable()),
+                    $crate::SortDirection::Desc => Box::new($column.lt(value.clone()).nullable()),
+                };
+                (
+                    Box::new($column.eq(value).nullable()) as BoxedCondition,
+                    match nulls_last {
+                        true => $crate::CombineConditions::combine(
+                            after,
+                            Box::new($column.is_null().nullable()),
+                            &$crate::AndOr::Or,
+                        ),
+                        false => after,
+                    },
+                )
+            }
+        }
+    }};
+}
+
+/*
+    Sortable fields of a table, used with dynamic_filter! in the same module (for BoxedQuery and
+    Condition), columns can be from any table of the query. Primary key fields are added to the sort
+    for keyset pagination, they have to identify a row of the query (include ids of joined tables
+    when they multiply rows)
+*/
+macro_rules! dynamic_sort {
+    (
+        $vis:vis Sort,
+        primary_key = [$($primary_key:ident),* $(,)?],
+        fields {
+            $($field:ident($type:ty) => $column:path),* $(,)?
+        }
+    ) => {
+        #[allow(non_camel_case_types)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        $vis enum SortField {
+            $($field,)*
+        }
+
+        $vis type Sort = $crate::SortKey<SortField>;
+
+        // Sort keys in order, after any existing order of the query
+        $vis fn apply_sort(query: BoxedQuery, sorts: Vec<Sort>) -> BoxedQuery {
+            sorts.into_iter().fold(query, |query, sort| match sort.field {
+                $(SortField::$field => $crate::sort_by!(query, sort, $column),)*
+            })
+        }
+
+        $vis fn create_list_query(
+            request: $crate::ListRequest<Condition, Sort>,
+        ) -> Result<BoxedQuery, $crate::FilterError> {
+            Ok(apply_sort(create_filtered_query(request.filter)?, request.sort))
+        }
+
+        // Sort values of a row, select with sort_selection() next to the item
+        #[derive(diesel::Queryable, Debug)]
+        $vis struct SortRow {
+            $($field: Option<$type>,)*
+        }
+
+        $vis fn sort_selection() -> ($(diesel::dsl::Nullable<$column>,)*) {
+            ($($column.nullable(),)*)
+        }
+
+        impl SortRow {
+            fn cursor(&self, sorts: &[Sort]) -> String {
+                $crate::keyset::encode_cursor(sorts.iter().map(|sort| match sort.field {
+                    $(SortField::$field => self
+                        .$field
+                        .as_ref()
+                        .map($crate::keyset::CursorValue::to_cursor),)*
+                }))
+            }
+        }
+
+        fn with_primary_key(mut sorts: Vec<Sort>) -> Vec<Sort> {
+            for field in [$(SortField::$primary_key),*] {
+                if !sorts.iter().any(|sort| sort.field == field) {
+                    sorts.push(Sort::asc(field));
+                }
+            }
+            sorts
+        }
+
+        // (k1 after v1) OR (k1 = v1 AND ((k2 after v2) OR (k2 = v2 AND ..)))
+        fn keyset_condition(sorts: &[Sort], cursor: &str) -> Result<BoxedCondition, $crate::FilterError> {
+            let values = $crate::keyset::decode_cursor(cursor, sorts.len())?;
+            let keys = sorts
+                .iter()
+                .zip(values)
+                .map(|(sort, value)| {
+                    Ok(match sort.field {
+                        $(SortField::$field => {
+                            let value = $crate::keyset::decode_value::<$type>(value)?;
+                            $crate::keyset_key!(sort, $column, value)
+                        })*
+                    })
+                })
+                .collect::<Result<Vec<_>, $crate::FilterError>>()?;
+
+            Ok(keys.into_iter().rev().fold(None, |condition, (equal, after)| {
+                Some(match condition {
+                    None => after,
+                    Some(condition) => $crate::CombineConditions::combine(
+                        after,
+                        $crate::CombineConditions::combine(equal, condition, &$crate::AndOr::And),
+                        &$crate::AndOr::Or,
+                    ),
+                })
+            }).unwrap_or_else(|| $crate::CombineConditions::constant(true)))
+        }
+
+        // Filtered and sorted rows after cursor (first page without cursor), with one extra row to
+        // tell if there are more, select with sort_selection() and pass the rows to next_page
+        $vis fn create_page_query(
+            request: $crate::ListRequest<Condition, Sort>,
+            cursor: Option<&str>,
+            limit: i64,
+        ) -> Result<BoxedQuery, $crate::FilterError> {
+            create_page_query_with(request, cursor, limit, &$crate::FilterOptions::default())
+        }
+
+        // Extra row is saturating, a limit of i64::MAX loads every row rather than overflowing
+        $vis fn create_page_query_with(
+            request: $crate::ListRequest<Condition, Sort>,
+            cursor: Option<&str>,
+            limit: i64,
+            options: &$crate::FilterOptions,
+        ) -> Result<BoxedQuery, $crate::FilterError> {
+            let sorts = with_primary_key(request.sort);
+            let query = create_filtered_query_with(request.filter, options)?;
+            let query = match cursor {
+                Some(cursor) => query.filter(keyset_condition(&sorts, cursor)?),
+                None => query,
+            };
+            Ok(apply_sort(query, sorts).limit(limit.saturating_add(1)))
+        }
+
+        // Sort is the same as in the page query request
+        $vis fn next_page<T>(
+            rows: Vec<(T, SortRow)>,
+            sort: Vec<Sort>,
+            limit: i64,
+        ) -> $crate::keyset::KeysetPage<T> {
+            let has_more = rows.len() as i64 > limit;
+            let mut rows = rows;
+            rows.truncate(limit.max(0) as usize);
+            let next_cursor = match (has_more, rows.last()) {
+                (true, Some((_, sort_row))) => Some(sort_row.cursor(&with_primary_key(sort))),
+                _ => None,
+            };
+
+            $crate::keyset::KeysetPage {
+                items: rows.into_iter().map(|(item, _)| item).collect(),
+                next_cursor,
+                has_more,
+            }
+        }
+    };
+}
+
 use boolean_filter;
+use date_filter;
+use dynamic_filter;
+use dynamic_sort;
+use keyset_key;
 use number_filter;
+use sort_by;
 use string_filter;
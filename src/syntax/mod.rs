@@ -0,0 +1,1161 @@
+/*
+    Filter syntaxes (text expressions, query strings, etc..) are parsed to table agnostic SyntaxTree,
+    with field names, operators and values, which is then converted to table's Condition via
+    FieldRegistry (implemented by dynamic_filter! macro), and the other way around for printing
+*/
+
+use std::{fmt, marker::PhantomData};
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::*;
+
+#[cfg(feature = "serde")]
+pub(crate) mod mongo;
+pub(crate) mod odata;
+pub(crate) mod query_string;
+pub(crate) mod rsql;
+pub(crate) mod text;
+
+// Names match serde names of filter variants
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Operator {
+    Equal,
+    NotEqual,
+    GreaterThan,
+    LowerThan,
+    GreaterOrEqual,
+    LowerOrEqual,
+    Between,
+    In,
+    NotIn,
+    Like,
+    NotLike,
+    StartsWith,
+    EndsWith,
+    Contains,
+    Wildcard,
+    NotWildcard,
+    EqualIgnoreCase,
+    ContainsIgnoreCase,
+    InIgnoreCase,
+    IsNull,
+    IsNotNull,
+    Before,
+    After,
+    OnDay,
+    Last,
+    OlderThan,
+    This,
+}
+
+const OPERATOR_NAMES: [(Operator, &str); 27] = [
+    (Operator::Equal, "eq"),
+    (Operator::NotEqual, "ne"),
+    (Operator::GreaterThan, "gt"),
+    (Operator::LowerThan, "lt"),
+    (Operator::GreaterOrEqual, "gte"),
+    (Operator::LowerOrEqual, "lte"),
+    (Operator::Between, "between"),
+    (Operator::In, "in"),
+    (Operator::NotIn, "not_in"),
+    (Operator::Like, "like"),
+    (Operator::NotLike, "not_like"),
+    (Operator::StartsWith, "starts_with"),
+    (Operator::EndsWith, "ends_with"),
+    (Operator::Contains, "contains"),
+    (Operator::Wildcard, "wildcard"),
+    (Operator::NotWildcard, "not_wildcard"),
+    (Operator::EqualIgnoreCase, "eq_ignore_case"),
+    (Operator::ContainsIgnoreCase, "contains_ignore_case"),
+    (Operator::InIgnoreCase, "in_ignore_case"),
+    (Operator::IsNull, "is_null"),
+    (Operator::IsNotNull, "is_not_null"),
+    (Operator::Before, "before"),
+    (Operator::After, "after"),
+    (Operator::OnDay, "on_day"),
+    (Operator::Last, "last"),
+    (Operator::OlderThan, "older_than"),
+    (Operator::This, "this"),
+];
+
+impl Operator {
+    pub(crate) fn from_name(name: &str) -> Option<Operator> {
+        OPERATOR_NAMES
+            .iter()
+            .find(|(_, operator_name)| *operator_name == name)
+            .map(|(operator, _)| *operator)
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        OPERATOR_NAMES
+            .iter()
+            .find(|(operator, _)| operator == self)
+            .map(|(_, name)| *name)
+            .unwrap()
+    }
+}
+
+// Same as serde names, singular unit is also accepted
+impl TimeUnit {
+    pub(crate) fn from_name(name: &str) -> Option<TimeUnit> {
+        match name.trim_end_matches('s') {
+            "minute" => Some(TimeUnit::Minutes),
+            "hour" => Some(TimeUnit::Hours),
+            "day" => Some(TimeUnit::Days),
+            "week" => Some(TimeUnit::Weeks),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            TimeUnit::Minutes => "minutes",
+            TimeUnit::Hours => "hours",
+            TimeUnit::Days => "days",
+            TimeUnit::Weeks => "weeks",
+        }
+    }
+}
+
+impl Period {
+    pub(crate) fn from_name(name: &str) -> Option<Period> {
+        match name {
+            "day" => Some(Period::Day),
+            "week" => Some(Period::Week),
+            "month" => Some(Period::Month),
+            "year" => Some(Period::Year),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Period::Day => "day",
+            Period::Week => "week",
+            Period::Month => "month",
+            Period::Year => "year",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    // For operators without a value (is_null and is_not_null)
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    // Also dates and times, and any value of a syntax that doesn't have types (i.e. query string)
+    String(String),
+    List(Vec<Value>),
+    Range {
+        from: Box<Value>,
+        to: Box<Value>,
+        from_inclusive: bool,
+        to_inclusive: bool,
+    },
+    Duration(u32, TimeUnit),
+    Period(Period),
+}
+
+pub(crate) type SyntaxTree = ConditionTree<FieldCondition>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FieldCondition {
+    pub(crate) field: String,
+    // Where the condition is in the input, for syntax errors (when known)
+    pub(crate) location: Option<Location>,
+    pub(crate) filter: FieldFilter,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Location {
+    // Byte offset of text syntaxes
+    Offset(usize),
+    // JSON pointer to the key of decoded documents, i.e. /$or/1/number_field
+    Path(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FieldFilter {
+    Comparison(Operator, Value),
+    // Inner statement conditions (i.e. person bike)
+    Relation(Vec<SyntaxTree>),
+    // Any row of inner statement matches (MongoDB $elemMatch), mapped to the table's correlated
+    // EXISTS field when it has one (bike_any, or bike_none when negated, as NOT IN of bike is never
+    // TRUE with a NULL owner_id) and to the relation field otherwise
+    Exists(Vec<SyntaxTree>),
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct SyntaxError {
+    pub(crate) location: Option<Location>,
+    pub(crate) kind: SyntaxErrorKind,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum SyntaxErrorKind {
+    UnexpectedCharacter(char),
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    UnterminatedString,
+    InvalidNumber(String),
+    UnknownField(String),
+    UnknownOperator(String),
+    UnsupportedOperator { field: String, operator: Operator },
+    InvalidValue { field: String, value: Value },
+    // Comparison on inner statement field, or inner statement conditions on a field with filter
+    ExpectedRelation(String),
+    ExpectedComparison(String),
+    // Valid in syntax but can't be mapped to filters (i.e. OData arithmetic)
+    Unsupported(String),
+    // Groups, negations or relations nested deeper than MAX_DEPTH
+    TooDeep,
+}
+
+impl SyntaxError {
+    // At byte offset in the input
+    pub(crate) fn new(position: Option<usize>, kind: SyntaxErrorKind) -> Self {
+        SyntaxError::at(position.map(Location::Offset), kind)
+    }
+
+    pub(crate) fn at_path(path: &str, kind: SyntaxErrorKind) -> Self {
+        SyntaxError::at(Some(Location::Path(path.to_string())), kind)
+    }
+
+    pub(crate) fn at(location: Option<Location>, kind: SyntaxErrorKind) -> Self {
+        SyntaxError { location, kind }
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            SyntaxErrorKind::UnexpectedCharacter(character) => {
+                write!(f, "unexpected character '{character}'")?
+            }
+            SyntaxErrorKind::UnexpectedToken(token) => write!(f, "unexpected '{token}'")?,
+            SyntaxErrorKind::UnexpectedEnd => write!(f, "unexpected end of input")?,
+            SyntaxErrorKind::UnterminatedString => write!(f, "unterminated string")?,
+            SyntaxErrorKind::InvalidNumber(number) => write!(f, "invalid number '{number}'")?,
+            SyntaxErrorKind::UnknownField(field) => write!(f, "unknown field '{field}'")?,
+            SyntaxErrorKind::UnknownOperator(operator) => {
+                write!(f, "unknown operator '{operator}'")?
+            }
+            SyntaxErrorKind::UnsupportedOperator { field, operator } => write!(
+                f,
+                "operator '{}' is not supported for field '{field}'",
+                operator.name()
+            )?,
+            SyntaxErrorKind::InvalidValue { field, value } => {
+                write!(f, "invalid value {value:?} for field '{field}'")?
+            }
+            SyntaxErrorKind::ExpectedRelation(field) => {
+                write!(f, "expected conditions for field '{field}'")?
+            }
+            SyntaxErrorKind::ExpectedComparison(field) => {
+                write!(f, "expected comparison for field '{field}'")?
+            }
+            SyntaxErrorKind::Unsupported(construct) => write!(f, "'{construct}' is not supported")?,
+            SyntaxErrorKind::TooDeep => write!(f, "nesting is deeper than {MAX_DEPTH}")?,
+        };
+        match &self.location {
+            Some(Location::Offset(position)) => write!(f, " at position {position}"),
+            Some(Location::Path(path)) => write!(f, " at '{path}'"),
+            None => Ok(()),
+        }
+    }
+}
+
+// Tokens shared by syntaxes, each syntax has it's own tokenizer
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Symbol(&'static str),
+}
+
+struct Lexed {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+// Parsers are recursive, nesting is limited for untrusted input not to overflow the stack
+const MAX_DEPTH: usize = 64;
+
+// Cursor over tokens, parse methods of each syntax are implemented for it's marker type (S)
+struct Parser<'a, S> {
+    input: &'a str,
+    tokens: Vec<Lexed>,
+    index: usize,
+    depth: usize,
+    syntax: PhantomData<S>,
+}
+
+impl<'a, S> Parser<'a, S> {
+    fn new(input: &'a str, tokens: Vec<Lexed>) -> Self {
+        Parser {
+            input,
+            tokens,
+            index: 0,
+            depth: 0,
+            syntax: PhantomData,
+        }
+    }
+
+    fn is_end(&self) -> bool {
+        self.index >= self.tokens.len()
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.index)
+            .map_or(self.input.len(), |lexed| lexed.start)
+    }
+
+    // Error for current token (or end of input)
+    fn unexpected(&self) -> SyntaxError {
+        match self.tokens.get(self.index) {
+            Some(Lexed { start, end, .. }) => SyntaxError::new(
+                Some(*start),
+                SyntaxErrorKind::UnexpectedToken(self.input[*start..*end].to_string()),
+            ),
+            None => SyntaxError::new(Some(self.input.len()), SyntaxErrorKind::UnexpectedEnd),
+        }
+    }
+
+    fn next(&mut self) -> Result<Token, SyntaxError> {
+        let token = match self.tokens.get(self.index) {
+            Some(lexed) => lexed.token.clone(),
+            None => return Err(self.unexpected()),
+        };
+        self.index += 1;
+        Ok(token)
+    }
+
+    // Error for the token that was just consumed by next()
+    fn unexpected_previous(&mut self) -> SyntaxError {
+        self.index -= 1;
+        self.unexpected()
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(
+            self.tokens.get(self.index),
+            Some(Lexed { token: Token::Ident(ident), .. }) if ident.eq_ignore_ascii_case(keyword)
+        )
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let is_keyword = self.is_keyword(keyword);
+        if is_keyword {
+            self.index += 1;
+        }
+        is_keyword
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), SyntaxError> {
+        match self.eat_keyword(keyword) {
+            true => Ok(()),
+            false => Err(self.unexpected()),
+        }
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let is_symbol = matches!(
+            self.tokens.get(self.index),
+            Some(Lexed { token: Token::Symbol(current), .. }) if *current == symbol
+        );
+        if is_symbol {
+            self.index += 1;
+        }
+        is_symbol
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), SyntaxError> {
+        match self.eat_symbol(symbol) {
+            true => Ok(()),
+            false => Err(self.unexpected()),
+        }
+    }
+
+    // Parse one level deeper (group, negation or relation)
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, SyntaxError>,
+    ) -> Result<T, SyntaxError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(SyntaxError::new(
+                Some(self.position()),
+                SyntaxErrorKind::TooDeep,
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn expect_ident(&mut self) -> Result<(String, usize), SyntaxError> {
+        let position = self.position();
+        match self.next()? {
+            Token::Ident(ident) => Ok((ident, position)),
+            _ => Err(self.unexpected_previous()),
+        }
+    }
+}
+
+// Conditions of a syntax (comparisons, function calls) and their values, combined by shared
+// and, or, not, brackets and lists of Parser
+trait ConditionSyntax: Sized {
+    // Symbols for or and and, besides the keywords (i.e. , and ; of RSQL)
+    const OR_SYMBOL: Option<&'static str> = None;
+    const AND_SYMBOL: Option<&'static str> = None;
+
+    fn parse_condition(parser: &mut Parser<'_, Self>) -> Result<SyntaxTree, SyntaxError>;
+    fn parse_value(parser: &mut Parser<'_, Self>) -> Result<Value, SyntaxError>;
+}
+
+impl<S: ConditionSyntax> Parser<'_, S> {
+    // Whole input, empty input is no conditions
+    fn parse_input<F: FieldRegistry>(mut self) -> Result<Vec<ConditionTree<F>>, SyntaxError> {
+        if self.is_end() {
+            return Ok(Vec::new());
+        }
+
+        let tree = self.parse_or()?;
+        if !self.is_end() {
+            return Err(self.unexpected());
+        }
+
+        into_conditions(tree)
+            .into_iter()
+            .map(ConditionTree::from_syntax_tree)
+            .collect()
+    }
+
+    // Precedence from lowest: or, and, not
+    fn parse_or(&mut self) -> Result<SyntaxTree, SyntaxError> {
+        self.nested(|parser| {
+            let mut trees = vec![parser.parse_and()?];
+            while parser.eat_keyword("or") || S::OR_SYMBOL.is_some_and(|or| parser.eat_symbol(or)) {
+                trees.push(parser.parse_and()?);
+            }
+            Ok(match trees.len() {
+                1 => trees.pop().unwrap(),
+                _ => ConditionTree::Or(trees),
+            })
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<SyntaxTree, SyntaxError> {
+        let mut trees = vec![self.parse_not()?];
+        while self.eat_keyword("and") || S::AND_SYMBOL.is_some_and(|and| self.eat_symbol(and)) {
+            trees.push(self.parse_not()?);
+        }
+        Ok(match trees.len() {
+            1 => trees.pop().unwrap(),
+            _ => ConditionTree::And(trees),
+        })
+    }
+
+    fn parse_not(&mut self) -> Result<SyntaxTree, SyntaxError> {
+        if self.eat_keyword("not") {
+            return Ok(ConditionTree::Not(Box::new(self.nested(Self::parse_not)?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<SyntaxTree, SyntaxError> {
+        if self.eat_symbol("(") {
+            let tree = self.parse_or()?;
+            self.expect_symbol(")")?;
+            return Ok(tree);
+        }
+        if self.eat_keyword("true") {
+            return Ok(ConditionTree::True);
+        }
+        if self.eat_keyword("false") {
+            return Ok(ConditionTree::False);
+        }
+
+        S::parse_condition(self)
+    }
+
+    // Values in brackets, (1, 2)
+    fn parse_list(&mut self) -> Result<Value, SyntaxError> {
+        self.expect_symbol("(")?;
+        let mut values = Vec::new();
+        if self.eat_symbol(")") {
+            return Ok(Value::List(values));
+        }
+        loop {
+            values.push(S::parse_value(self)?);
+            if !self.eat_symbol(",") {
+                break;
+            }
+        }
+        self.expect_symbol(")")?;
+        Ok(Value::List(values))
+    }
+}
+
+// Top level And is a list of conditions
+fn into_conditions(tree: SyntaxTree) -> Vec<SyntaxTree> {
+    match tree {
+        ConditionTree::And(trees) => trees,
+        tree => vec![tree],
+    }
+}
+
+// Implemented by Field enum of every table (see dynamic_filter! macro)
+pub(crate) trait FieldRegistry: Sized {
+    // Field names, known at compile time (i.e. count can't be a field of counted tables)
+    const FIELDS: &'static [&'static str];
+
+    fn from_field_condition(condition: FieldCondition) -> Result<Self, SyntaxError>;
+    fn to_field_condition(&self) -> FieldCondition;
+
+    // Exists condition that has a field with suffix in this table, i.e. bike_any
+    fn exists_field(condition: &FieldCondition, suffix: &str) -> bool {
+        matches!(condition.filter, FieldFilter::Exists(_))
+            && Self::FIELDS.iter().any(|field| {
+                field
+                    .strip_suffix(suffix)
+                    .and_then(|field| field.strip_suffix('_'))
+                    == Some(condition.field.as_str())
+            })
+    }
+}
+
+// Exists condition as the relation of it's EXISTS (any) or NOT EXISTS (none) field, i.e. bike_any
+fn exists(condition: FieldCondition, suffix: &str) -> FieldCondition {
+    let FieldFilter::Exists(trees) = condition.filter else {
+        return condition;
+    };
+    FieldCondition {
+        field: format!("{}_{suffix}", condition.field),
+        location: condition.location,
+        filter: FieldFilter::Relation(trees),
+    }
+}
+
+// Syntax tree as is, without field validation
+impl FieldRegistry for FieldCondition {
+    const FIELDS: &'static [&'static str] = &[];
+
+    fn from_field_condition(condition: FieldCondition) -> Result<Self, SyntaxError> {
+        Ok(condition)
+    }
+
+    fn to_field_condition(&self) -> FieldCondition {
+        self.clone()
+    }
+}
+
+// Implemented by field types, filters are converted from and to comparison, and inner statement
+// conditions (Vec<Condition> of another table) from and to relation
+pub(crate) trait FieldSyntax: Sized {
+    fn from_field_filter(
+        filter: FieldFilter,
+        field: &str,
+        location: Option<Location>,
+    ) -> Result<Self, SyntaxError>;
+    fn to_field_filter(&self) -> FieldFilter;
+}
+
+impl<F: FieldRegistry> ConditionTree<F> {
+    pub(crate) fn from_syntax_tree(tree: SyntaxTree) -> Result<Self, SyntaxError> {
+        let from_syntax_trees = |trees: Vec<SyntaxTree>| {
+            trees
+                .into_iter()
+                .map(ConditionTree::from_syntax_tree)
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(match tree {
+            ConditionTree::And(trees) => ConditionTree::And(from_syntax_trees(trees)?),
+            ConditionTree::Or(trees) => ConditionTree::Or(from_syntax_trees(trees)?),
+            ConditionTree::Not(tree) => match *tree {
+                ConditionTree::Field(condition) if F::exists_field(&condition, "none") => {
+                    ConditionTree::Field(F::from_field_condition(exists(condition, "none"))?)
+                }
+                tree => ConditionTree::Not(Box::new(Self::from_syntax_tree(tree)?)),
+            },
+            ConditionTree::True => ConditionTree::True,
+            ConditionTree::False => ConditionTree::False,
+            ConditionTree::Field(condition) if F::exists_field(&condition, "any") => {
+                ConditionTree::Field(F::from_field_condition(exists(condition, "any"))?)
+            }
+            ConditionTree::Field(FieldCondition {
+                field,
+                location,
+                filter: FieldFilter::Exists(trees),
+            }) => ConditionTree::Field(F::from_field_condition(FieldCondition {
+                field,
+                location,
+                filter: FieldFilter::Relation(trees),
+            })?),
+            ConditionTree::Field(condition) => {
+                ConditionTree::Field(F::from_field_condition(condition)?)
+            }
+        })
+    }
+
+    pub(crate) fn to_syntax_tree(&self) -> SyntaxTree {
+        let to_syntax_trees = |conditions: &Vec<ConditionTree<F>>| {
+            conditions
+                .iter()
+                .map(ConditionTree::to_syntax_tree)
+                .collect()
+        };
+
+        match self {
+            ConditionTree::And(conditions) => ConditionTree::And(to_syntax_trees(conditions)),
+            ConditionTree::Or(conditions) => ConditionTree::Or(to_syntax_trees(conditions)),
+            ConditionTree::Not(condition) => {
+                ConditionTree::Not(Box::new(condition.to_syntax_tree()))
+            }
+            ConditionTree::True => ConditionTree::True,
+            ConditionTree::False => ConditionTree::False,
+            ConditionTree::Field(field) => ConditionTree::Field(field.to_field_condition()),
+        }
+    }
+}
+
+impl<F: FieldRegistry> FieldSyntax for Vec<ConditionTree<F>> {
+    fn from_field_filter(
+        filter: FieldFilter,
+        field: &str,
+        location: Option<Location>,
+    ) -> Result<Self, SyntaxError> {
+        match filter {
+            FieldFilter::Relation(trees) | FieldFilter::Exists(trees) => trees
+                .into_iter()
+                .map(ConditionTree::from_syntax_tree)
+                .collect(),
+            FieldFilter::Comparison(..) => Err(SyntaxError::at(
+                location,
+                SyntaxErrorKind::ExpectedRelation(field.to_string()),
+            )),
+        }
+    }
+
+    fn to_field_filter(&self) -> FieldFilter {
+        FieldFilter::Relation(self.iter().map(ConditionTree::to_syntax_tree).collect())
+    }
+}
+
+// Count is a condition among inner statement conditions, i.e. bike_count(color = "orange" and
+// count >= 2), thus a table with count field can't be counted by its conditions (RelationCount of
+// it doesn't compile)
+const COUNT_FIELD: &str = "count";
+
+// == of str is not const
+const fn same_name(name: &str, other: &str) -> bool {
+    let (name, other) = (name.as_bytes(), other.as_bytes());
+    if name.len() != other.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < name.len() {
+        if name[index] != other[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+pub(crate) const fn defines_field(fields: &[&str], field: &str) -> bool {
+    let mut index = 0;
+    while index < fields.len() {
+        if same_name(fields[index], field) {
+            return true;
+        }
+        index += 1;
+    }
+    false
+}
+
+impl<F: FieldRegistry> FieldSyntax for RelationCount<ConditionTree<F>> {
+    fn from_field_filter(
+        filter: FieldFilter,
+        field: &str,
+        location: Option<Location>,
+    ) -> Result<Self, SyntaxError> {
+        const {
+            assert!(
+                !defines_field(F::FIELDS, COUNT_FIELD),
+                "count of a table with count field would be ambiguous"
+            )
+        };
+
+        let FieldFilter::Relation(trees) = filter else {
+            return Err(SyntaxError::at(
+                location,
+                SyntaxErrorKind::ExpectedRelation(field.to_string()),
+            ));
+        };
+
+        let mut conditions = Vec::new();
+        let mut count = None;
+        for tree in trees {
+            match tree {
+                // Another count is an unknown field of inner statement
+                ConditionTree::Field(condition)
+                    if condition.field == COUNT_FIELD && count.is_none() =>
+                {
+                    count = Some(NumberFilter::from_field_filter(
+                        condition.filter,
+                        COUNT_FIELD,
+                        condition.location,
+                    )?);
+                }
+                tree => conditions.push(ConditionTree::from_syntax_tree(tree)?),
+            }
+        }
+
+        Ok(RelationCount {
+            conditions,
+            count: count.ok_or_else(|| {
+                SyntaxError::at(
+                    location,
+                    SyntaxErrorKind::ExpectedComparison(format!("{field}.{COUNT_FIELD}")),
+                )
+            })?,
+        })
+    }
+
+    fn to_field_filter(&self) -> FieldFilter {
+        let count = ConditionTree::Field(FieldCondition {
+            field: COUNT_FIELD.to_string(),
+            location: None,
+            filter: self.count.to_field_filter(),
+        });
+        FieldFilter::Relation(
+            self.conditions
+                .iter()
+                .map(ConditionTree::to_syntax_tree)
+                .chain(std::iter::once(count))
+                .collect(),
+        )
+    }
+}
+
+// Values of filters, parsed leniently from strings, since not every syntax has types
+pub(crate) trait SyntaxValue: Sized {
+    fn from_value(value: Value) -> Option<Self>;
+    fn to_value(&self) -> Value;
+}
+
+impl SyntaxValue for i32 {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Integer(value) => value.try_into().ok(),
+            Value::String(value) => value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Integer((*self).into())
+    }
+}
+
+impl SyntaxValue for i64 {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Integer(value) => Some(value),
+            Value::String(value) => value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Integer(*self)
+    }
+}
+
+impl SyntaxValue for f64 {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Float(value) => Some(value),
+            Value::Integer(value) => Some(value as f64),
+            Value::String(value) => value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Float(*self)
+    }
+}
+
+impl SyntaxValue for String {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.clone())
+    }
+}
+
+impl SyntaxValue for bool {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Bool(value) => Some(value),
+            Value::String(value) => value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMATS: [&str; 2] = ["%H:%M:%S%.f", "%H:%M"];
+const DATE_TIME_FORMATS: [&str; 3] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+];
+
+impl SyntaxValue for NaiveDate {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::String(value) => NaiveDate::parse_from_str(&value, DATE_FORMAT).ok(),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.format(DATE_FORMAT).to_string())
+    }
+}
+
+impl SyntaxValue for NaiveTime {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::String(value) => TIME_FORMATS
+                .iter()
+                .find_map(|format| NaiveTime::parse_from_str(&value, format).ok()),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.format(TIME_FORMATS[0]).to_string())
+    }
+}
+
+impl SyntaxValue for NaiveDateTime {
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::String(value) => DATE_TIME_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(&value, format).ok()),
+            _ => None,
+        }
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(self.format(DATE_TIME_FORMATS[0]).to_string())
+    }
+}
+
+fn list_value<T: SyntaxValue>(value: Value) -> Option<Vec<T>> {
+    match value {
+        Value::List(values) => values.into_iter().map(T::from_value).collect(),
+        _ => None,
+    }
+}
+
+fn list_to_value<T: SyntaxValue>(values: &[T]) -> Value {
+    Value::List(values.iter().map(T::to_value).collect())
+}
+
+// Helper for FieldSyntax implementation of filters, operator and value are mapped to filter
+// with a closure returning None when value is invalid
+fn comparison_filter<T>(
+    filter: FieldFilter,
+    field: &str,
+    location: Option<Location>,
+    to_filter: impl FnOnce(Operator, Value) -> Result<Option<T>, Operator>,
+) -> Result<T, SyntaxError> {
+    let (operator, value) = match filter {
+        FieldFilter::Comparison(operator, value) => (operator, value),
+        FieldFilter::Relation(_) | FieldFilter::Exists(_) => {
+            return Err(SyntaxError::at(
+                location,
+                SyntaxErrorKind::ExpectedComparison(field.to_string()),
+            ))
+        }
+    };
+
+    let field = field.to_string();
+    match to_filter(operator, value.clone()) {
+        Ok(Some(filter)) => Ok(filter),
+        Ok(None) => Err(SyntaxError::at(
+            location,
+            SyntaxErrorKind::InvalidValue { field, value },
+        )),
+        Err(operator) => Err(SyntaxError::at(
+            location,
+            SyntaxErrorKind::UnsupportedOperator { field, operator },
+        )),
+    }
+}
+
+fn null_value(value: Value) -> Option<()> {
+    matches!(value, Value::Null).then_some(())
+}
+
+fn range_value<T: SyntaxValue>(value: Value) -> Option<(T, T, bool, bool)> {
+    match value {
+        Value::Range {
+            from,
+            to,
+            from_inclusive,
+            to_inclusive,
+        } => Some((
+            T::from_value(*from)?,
+            T::from_value(*to)?,
+            from_inclusive,
+            to_inclusive,
+        )),
+        _ => None,
+    }
+}
+
+fn range_to_value<T: SyntaxValue>(
+    from: &T,
+    to: &T,
+    from_inclusive: bool,
+    to_inclusive: bool,
+) -> Value {
+    Value::Range {
+        from: Box::new(from.to_value()),
+        to: Box::new(to.to_value()),
+        from_inclusive,
+        to_inclusive,
+    }
+}
+
+impl<T: SyntaxValue> FieldSyntax for NumberFilter<T> {
+    fn from_field_filter(
+        filter: FieldFilter,
+        field: &str,
+        location: Option<Location>,
+    ) -> Result<Self, SyntaxError> {
+        comparison_filter(filter, field, location, |operator, value| {
+            Ok(match operator {
+                Operator::Equal => T::from_value(value).map(NumberFilter::Equal),
+                Operator::NotEqual => T::from_value(value).map(NumberFilter::NotEqual),
+                Operator::GreaterThan => T::from_value(value).map(NumberFilter::GreaterThen),
+                Operator::LowerThan => T::from_value(value).map(NumberFilter::LowerThen),
+                Operator::GreaterOrEqual => T::from_value(value).map(NumberFilter::GreaterOrEqual),
+                Operator::LowerOrEqual => T::from_value(value).map(NumberFilter::LowerOrEqual),
+                Operator::Between => {
+                    range_value(value).map(|(from, to, from_inclusive, to_inclusive)| {
+                        NumberFilter::Between {
+                            from,
+                            to,
+                            from_inclusive,
+                            to_inclusive,
+                        }
+                    })
+                }
+                Operator::In => list_value(value).map(NumberFilter::In),
+                Operator::NotIn => list_value(value).map(NumberFilter::NotIn),
+                Operator::IsNull => null_value(value).map(|_| NumberFilter::IsNull),
+                Operator::IsNotNull => null_value(value).map(|_| NumberFilter::IsNotNull),
+                operator => return Err(operator),
+            })
+        })
+    }
+
+    fn to_field_filter(&self) -> FieldFilter {
+        let (operator, value) = match self {
+            NumberFilter::Equal(value) => (Operator::Equal, value.to_value()),
+            NumberFilter::NotEqual(value) => (Operator::NotEqual, value.to_value()),
+            NumberFilter::GreaterThen(value) => (Operator::GreaterThan, value.to_value()),
+            NumberFilter::LowerThen(value) => (Operator::LowerThan, value.to_value()),
+            NumberFilter::GreaterOrEqual(value) => (Operator::GreaterOrEqual, value.to_value()),
+            NumberFilter::LowerOrEqual(value) => (Operator::LowerOrEqual, value.to_value()),
+            NumberFilter::Between {
+                from,
+                to,
+                from_inclusive,
+                to_inclusive,
+            } => (
+                Operator::Between,
+                range_to_value(from, to, *from_inclusive, *to_inclusive),
+            ),
+            NumberFilter::In(values) => (Operator::In, list_to_value(values)),
+            NumberFilter::NotIn(values) => (Operator::NotIn, list_to_value(values)),
+            NumberFilter::IsNull => (Operator::IsNull, Value::Null),
+            NumberFilter::IsNotNull => (Operator::IsNotNull, Value::Null),
+        };
+        FieldFilter::Comparison(operator, value)
+    }
+}
+
+impl FieldSyntax for StringFilter {
+    fn from_field_filter(
+        filter: FieldFilter,
+        field: &str,
+        location: Option<Location>,
+    ) -> Result<Self, SyntaxError> {
+        comparison_filter(filter, field, location, |operator, value| {
+            Ok(match operator {
+                Operator::Equal => String::from_value(value).map(StringFilter::Equal),
+                Operator::NotEqual => String::from_value(value).map(StringFilter::NotEqual),
+                Operator::Like => String::from_value(value).map(StringFilter::Like),
+                Operator::NotLike => String::from_value(value).map(StringFilter::NotLike),
+                Operator::In => list_value(value).map(StringFilter::In),
+                Operator::NotIn => list_value(value).map(StringFilter::NotIn),
+                Operator::StartsWith => String::from_value(value).map(StringFilter::StartsWith),
+                Operator::EndsWith => String::from_value(value).map(StringFilter::EndsWith),
+                Operator::Contains => String::from_value(value).map(StringFilter::Contains),
+                Operator::Wildcard => String::from_value(value).map(StringFilter::Wildcard),
+                Operator::NotWildcard => String::from_value(value).map(StringFilter::NotWildcard),
+                Operator::EqualIgnoreCase => {
+                    String::from_value(value).map(StringFilter::EqualIgnoreCase)
+                }
+                Operator::ContainsIgnoreCase => {
+                    String::from_value(value).map(StringFilter::ContainsIgnoreCase)
+                }
+                Operator::InIgnoreCase => list_value(value).map(StringFilter::InIgnoreCase),
+                Operator::IsNull => null_value(value).map(|_| StringFilter::IsNull),
+                Operator::IsNotNull => null_value(value).map(|_| StringFilter::IsNotNull),
+                operator => return Err(operator),
+            })
+        })
+    }
+
+    fn to_field_filter(&self) -> FieldFilter {
+        let (operator, value) = match self {
+            StringFilter::Equal(value) => (Operator::Equal, value.to_value()),
+            StringFilter::NotEqual(value) => (Operator::NotEqual, value.to_value()),
+            StringFilter::Like(value) => (Operator::Like, value.to_value()),
+            StringFilter::NotLike(value) => (Operator::NotLike, value.to_value()),
+            StringFilter::In(values) => (Operator::In, list_to_value(values)),
+            StringFilter::NotIn(values) => (Operator::NotIn, list_to_value(values)),
+            StringFilter::StartsWith(value) => (Operator::StartsWith, value.to_value()),
+            StringFilter::EndsWith(value) => (Operator::EndsWith, value.to_value()),
+            StringFilter::Contains(value) => (Operator::Contains, value.to_value()),
+            StringFilter::Wildcard(value) => (Operator::Wildcard, value.to_value()),
+            StringFilter::NotWildcard(value) => (Operator::NotWildcard, value.to_value()),
+            StringFilter::EqualIgnoreCase(value) => (Operator::EqualIgnoreCase, value.to_value()),
+            StringFilter::ContainsIgnoreCase(value) => {
+                (Operator::ContainsIgnoreCase, value.to_value())
+            }
+            StringFilter::InIgnoreCase(values) => (Operator::InIgnoreCase, list_to_value(values)),
+            StringFilter::IsNull => (Operator::IsNull, Value::Null),
+            StringFilter::IsNotNull => (Operator::IsNotNull, Value::Null),
+        };
+        FieldFilter::Comparison(operator, value)
+    }
+}
+
+impl FieldSyntax for BooleanFilter {
+    fn from_field_filter(
+        filter: FieldFilter,
+        field: &str,
+        location: Option<Location>,
+    ) -> Result<Self, SyntaxError> {
+        comparison_filter(filter, field, location, |operator, value| {
+            Ok(match operator {
+                Operator::Equal => bool::from_value(value).map(|value| match value {
+                    true => BooleanFilter::True,
+                    false => BooleanFilter::False,
+                }),
+                Operator::IsNull => null_value(value).map(|_| BooleanFilter::IsNull),
+                Operator::IsNotNull => null_value(value).map(|_| BooleanFilter::IsNotNull),
+                operator => return Err(operator),
+            })
+        })
+    }
+
+    fn to_field_filter(&self) -> FieldFilter {
+        let (operator, value) = match self {
+            BooleanFilter::True => (Operator::Equal, Value::Bool(true)),
+            BooleanFilter::False => (Operator::Equal, Value::Bool(false)),
+            BooleanFilter::IsNull => (Operator::IsNull, Value::Null),
+            BooleanFilter::IsNotNull => (Operator::IsNotNull, Value::Null),
+        };
+        FieldFilter::Comparison(operator, value)
+    }
+}
+
+impl<T: SyntaxValue> FieldSyntax for DateTimeFilter<T> {
+    fn from_field_filter(
+        filter: FieldFilter,
+        field: &str,
+        location: Option<Location>,
+    ) -> Result<Self, SyntaxError> {
+        comparison_filter(filter, field, location, |operator, value| {
+            Ok(match operator {
+                Operator::Equal => T::from_value(value).map(DateTimeFilter::Equal),
+                Operator::Before | Operator::LowerThan => {
+                    T::from_value(value).map(DateTimeFilter::Before)
+                }
+                Operator::After | Operator::GreaterThan => {
+                    T::from_value(value).map(DateTimeFilter::After)
+                }
+                // Only inclusive
+                Operator::Between => match range_value(value) {
+                    Some((from, to, true, true)) => Some(DateTimeFilter::Between { from, to }),
+                    _ => None,
+                },
+                Operator::OnDay => NaiveDate::from_value(value).map(DateTimeFilter::OnDay),
+                Operator::Last => match value {
+                    Value::Duration(number, unit) => Some(DateTimeFilter::Last(number, unit)),
+                    _ => None,
+                },
+                Operator::OlderThan => match value {
+                    Value::Duration(number, unit) => Some(DateTimeFilter::OlderThan(number, unit)),
+                    _ => None,
+                },
+                Operator::This => match value {
+                    Value::Period(period) => Some(DateTimeFilter::This(period)),
+                    _ => None,
+                },
+                Operator::IsNull => null_value(value).map(|_| DateTimeFilter::IsNull),
+                Operator::IsNotNull => null_value(value).map(|_| DateTimeFilter::IsNotNull),
+                operator => return Err(operator),
+            })
+        })
+    }
+
+    fn to_field_filter(&self) -> FieldFilter {
+        let (operator, value) = match self {
+            DateTimeFilter::Equal(value) => (Operator::Equal, value.to_value()),
+            DateTimeFilter::Before(value) => (Operator::Before, value.to_value()),
+            DateTimeFilter::After(value) => (Operator::After, value.to_value()),
+            DateTimeFilter::Between { from, to } => {
+                (Operator::Between, range_to_value(from, to, true, true))
+            }
+            DateTimeFilter::OnDay(day) => (Operator::OnDay, day.to_value()),
+            DateTimeFilter::Last(number, unit) => (Operator::Last, Value::Duration(*number, *unit)),
+            DateTimeFilter::OlderThan(number, unit) => {
+                (Operator::OlderThan, Value::Duration(*number, *unit))
+            }
+            DateTimeFilter::This(period) => (Operator::This, Value::Period(*period)),
+            DateTimeFilter::IsNull => (Operator::IsNull, Value::Null),
+            DateTimeFilter::IsNotNull => (Operator::IsNotNull, Value::Null),
+        };
+        FieldFilter::Comparison(operator, value)
+    }
+}
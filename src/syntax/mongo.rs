@@ -0,0 +1,376 @@
+/*
+    MongoDB style JSON queries, i.e.
+    {"$and": [{"number_field": {"$gt": 1}}, {"$or": [{"bool_field": true}]}]}
+    Keys of a document are And, $elemMatch (or dotted key) on inner statement field is it's conditions,
+    $regex is only supported when it maps to a string filter (anchors and escaped characters),
+    negations match null as missing fields do, and errors are at the JSON pointer of the key
+*/
+
+use serde_json::{Map, Value as Json};
+
+use super::*;
+
+// Query operators that are valid in MongoDB but can't be mapped to filters
+const UNSUPPORTED_OPERATORS: [&str; 11] = [
+    "$where",
+    "$expr",
+    "$text",
+    "$jsonSchema",
+    "$all",
+    "$size",
+    "$mod",
+    "$type",
+    "$geoWithin",
+    "$near",
+    "$comment",
+];
+
+const REGEX_SPECIAL_CHARACTERS: &str = r".*+?()[]{}|^$\";
+
+fn unexpected(path: &str, json: &Json) -> SyntaxError {
+    SyntaxError::at_path(path, SyntaxErrorKind::UnexpectedToken(json.to_string()))
+}
+
+fn unsupported(path: &str, construct: &str) -> SyntaxError {
+    SyntaxError::at_path(path, SyntaxErrorKind::Unsupported(construct.to_string()))
+}
+
+fn unknown_operator(path: &str, operator: &str) -> SyntaxError {
+    match UNSUPPORTED_OPERATORS.contains(&operator) {
+        true => unsupported(path, operator),
+        false => SyntaxError::at_path(path, SyntaxErrorKind::UnknownOperator(operator.to_string())),
+    }
+}
+
+// JSON pointer of a key or array index, ~ and / in keys are escaped
+fn child_path(path: &str, key: &str) -> String {
+    format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+// Multiple conditions in a document are And
+fn into_tree(mut trees: Vec<SyntaxTree>) -> SyntaxTree {
+    match trees.len() {
+        1 => trees.pop().unwrap(),
+        _ => ConditionTree::And(trees),
+    }
+}
+
+// $ne, $nin, $not and $nor match documents where the field is missing or null, while SQL NOT of a
+// comparison with NULL is unknown, thus negated comparisons are Or'ed with IS NULL. Inner statements
+// are Exists, negated as bike_none (NOT EXISTS) when the table has it, see FieldFilter::Exists
+fn negate(tree: SyntaxTree) -> SyntaxTree {
+    match tree {
+        ConditionTree::And(trees) => ConditionTree::Or(trees.into_iter().map(negate).collect()),
+        ConditionTree::Or(trees) => ConditionTree::And(trees.into_iter().map(negate).collect()),
+        ConditionTree::Not(tree) => *tree,
+        ConditionTree::True => ConditionTree::False,
+        ConditionTree::False => ConditionTree::True,
+        ConditionTree::Field(condition) => {
+            let with_filter = |operator, value| {
+                ConditionTree::Field(FieldCondition {
+                    filter: FieldFilter::Comparison(operator, value),
+                    ..condition.clone()
+                })
+            };
+            let negated = match &condition.filter {
+                FieldFilter::Comparison(Operator::IsNull, value) => {
+                    return with_filter(Operator::IsNotNull, value.clone())
+                }
+                FieldFilter::Comparison(Operator::IsNotNull, value) => {
+                    return with_filter(Operator::IsNull, value.clone())
+                }
+                FieldFilter::Relation(_) | FieldFilter::Exists(_) => {
+                    return ConditionTree::Not(Box::new(ConditionTree::Field(condition.clone())))
+                }
+                // Not rather than the inverse operator, which not every filter has
+                FieldFilter::Comparison(..) => {
+                    ConditionTree::Not(Box::new(ConditionTree::Field(condition.clone())))
+                }
+            };
+            ConditionTree::Or(vec![negated, with_filter(Operator::IsNull, Value::Null)])
+        }
+    }
+}
+
+fn field_tree(field: &str, path: String, filter: FieldFilter) -> SyntaxTree {
+    ConditionTree::Field(FieldCondition {
+        field: field.to_string(),
+        location: Some(Location::Path(path)),
+        filter,
+    })
+}
+
+fn decode_document(path: &str, document: &Json) -> Result<Vec<SyntaxTree>, SyntaxError> {
+    let Json::Object(document) = document else {
+        return Err(unexpected(path, document));
+    };
+
+    document
+        .iter()
+        .map(|(key, json)| {
+            let path = child_path(path, key);
+            Ok(match key.as_str() {
+                "$and" => ConditionTree::And(decode_documents(&path, json)?),
+                "$or" => ConditionTree::Or(decode_documents(&path, json)?),
+                "$nor" => negate(ConditionTree::Or(decode_documents(&path, json)?)),
+                operator if operator.starts_with('$') => {
+                    return Err(unknown_operator(&path, operator))
+                }
+                key => decode_field(&path, key, json)?,
+            })
+        })
+        .collect()
+}
+
+fn decode_documents(path: &str, json: &Json) -> Result<Vec<SyntaxTree>, SyntaxError> {
+    let Json::Array(documents) = json else {
+        return Err(unexpected(path, json));
+    };
+
+    documents
+        .iter()
+        .enumerate()
+        .map(|(index, document)| {
+            Ok(into_tree(decode_document(
+                &child_path(path, &index.to_string()),
+                document,
+            )?))
+        })
+        .collect()
+}
+
+fn decode_field(path: &str, key: &str, json: &Json) -> Result<SyntaxTree, SyntaxError> {
+    // Dotted key, bike.color
+    if let Some((field, key)) = key.split_once('.') {
+        let filter = FieldFilter::Exists(vec![decode_field(path, key, json)?]);
+        return Ok(field_tree(field, path.to_string(), filter));
+    }
+
+    match json {
+        Json::Object(operators) if operators.keys().any(|key| key.starts_with('$')) => {
+            let trees = operators
+                .iter()
+                // Read with $regex
+                .filter(|(operator, _)| *operator != "$options")
+                .map(|(operator, json)| {
+                    let path = child_path(path, operator);
+                    decode_operator(&path, key, operator, json, operators)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(into_tree(trees))
+        }
+        // Embedded document equality
+        Json::Object(_) | Json::Array(_) => Err(unexpected(path, json)),
+        Json::Null => Ok(field_tree(
+            key,
+            path.to_string(),
+            FieldFilter::Comparison(Operator::IsNull, Value::Null),
+        )),
+        json => {
+            let filter = FieldFilter::Comparison(Operator::Equal, scalar(path, json)?);
+            Ok(field_tree(key, path.to_string(), filter))
+        }
+    }
+}
+
+fn decode_operator(
+    path: &str,
+    field: &str,
+    operator: &str,
+    json: &Json,
+    operators: &Map<String, Json>,
+) -> Result<SyntaxTree, SyntaxError> {
+    let (operator, value) = match (operator, json) {
+        ("$eq", Json::Null) => (Operator::IsNull, Value::Null),
+        ("$eq", json) => (Operator::Equal, scalar(path, json)?),
+        ("$ne", json) => {
+            let equal = decode_operator(path, field, "$eq", json, operators)?;
+            return Ok(negate(equal));
+        }
+        ("$gt", json) => (Operator::GreaterThan, scalar(path, json)?),
+        ("$gte", json) => (Operator::GreaterOrEqual, scalar(path, json)?),
+        ("$lt", json) => (Operator::LowerThan, scalar(path, json)?),
+        ("$lte", json) => (Operator::LowerOrEqual, scalar(path, json)?),
+        ("$in", Json::Array(values)) => (Operator::In, scalars(path, values)?),
+        ("$nin", Json::Array(_)) => {
+            let in_values = decode_operator(path, field, "$in", json, operators)?;
+            return Ok(negate(in_values));
+        }
+        ("$exists", Json::Bool(true)) => (Operator::IsNotNull, Value::Null),
+        ("$exists", Json::Bool(false)) => (Operator::IsNull, Value::Null),
+        ("$regex", Json::String(pattern)) => {
+            let options = match operators.get("$options") {
+                Some(Json::String(options)) => options.as_str(),
+                Some(json) => return Err(unexpected(path, json)),
+                None => "",
+            };
+            let (operator, value) = regex_filter(path, pattern, options)?;
+            (operator, Value::String(value))
+        }
+        ("$not", Json::Object(_)) => return Ok(negate(decode_field(path, field, json)?)),
+        ("$elemMatch", Json::Object(_)) => {
+            let filter = FieldFilter::Exists(decode_document(path, json)?);
+            return Ok(field_tree(field, path.to_string(), filter));
+        }
+        ("$in" | "$nin" | "$exists" | "$regex" | "$not" | "$elemMatch", json) => {
+            return Err(unexpected(path, json))
+        }
+        (operator, _) => return Err(unknown_operator(path, operator)),
+    };
+
+    Ok(field_tree(
+        field,
+        path.to_string(),
+        FieldFilter::Comparison(operator, value),
+    ))
+}
+
+fn scalar(path: &str, json: &Json) -> Result<Value, SyntaxError> {
+    Ok(match json {
+        Json::Bool(value) => Value::Bool(*value),
+        Json::Number(number) => match number.as_i64() {
+            Some(integer) => Value::Integer(integer),
+            None => Value::Float(number.as_f64().ok_or_else(|| unexpected(path, json))?),
+        },
+        Json::String(value) => Value::String(value.clone()),
+        json => return Err(unexpected(path, json)),
+    })
+}
+
+fn scalars(path: &str, values: &[Json]) -> Result<Value, SyntaxError> {
+    Ok(Value::List(
+        values
+            .iter()
+            .enumerate()
+            .map(|(index, json)| scalar(&child_path(path, &index.to_string()), json))
+            .collect::<Result<_, _>>()?,
+    ))
+}
+
+// ^abc$ is equal, and with i option ^abc$ is equal ignoring case and abc contains ignoring case.
+// Regex is case sensitive, but LIKE of starts with, ends with and contains isn't in SQLite (for
+// ASCII), so those are only supported as case insensitive, where there is a filter
+fn regex_filter(
+    path: &str,
+    pattern: &str,
+    options: &str,
+) -> Result<(Operator, String), SyntaxError> {
+    let (anchored_start, pattern) = match pattern.strip_prefix('^') {
+        Some(pattern) => (true, pattern),
+        None => (false, pattern),
+    };
+    let (anchored_end, pattern) = match pattern.strip_suffix('$') {
+        Some(stripped) if !stripped.ends_with('\\') || stripped.ends_with("\\\\") => {
+            (true, stripped)
+        }
+        _ => (false, pattern),
+    };
+
+    let mut value = String::new();
+    let mut chars = pattern.chars();
+    while let Some(character) = chars.next() {
+        match character {
+            '\\' => match chars.next() {
+                Some(character) if REGEX_SPECIAL_CHARACTERS.contains(character) => {
+                    value.push(character)
+                }
+                _ => return Err(unsupported(path, "$regex")),
+            },
+            character if REGEX_SPECIAL_CHARACTERS.contains(character) => {
+                return Err(unsupported(path, "$regex"))
+            }
+            character => value.push(character),
+        }
+    }
+
+    let operator = match (options, anchored_start, anchored_end) {
+        ("", true, true) => Operator::Equal,
+        ("", _, _) => return Err(unsupported(path, "case sensitive $regex")),
+        ("i", true, true) => Operator::EqualIgnoreCase,
+        ("i", false, false) => Operator::ContainsIgnoreCase,
+        ("i", _, _) => return Err(unsupported(path, "$regex")),
+        _ => return Err(unsupported(path, "$options")),
+    };
+
+    Ok((operator, value))
+}
+
+pub(crate) fn decode<F: FieldRegistry>(query: &Json) -> Result<Vec<ConditionTree<F>>, SyntaxError> {
+    decode_document("", query)?
+        .into_iter()
+        .map(ConditionTree::from_syntax_tree)
+        .collect()
+}
+
+#[test]
+fn test() {
+    use serde_json::json;
+
+    let as_text = |query: Json| text::print(&decode::<FieldCondition>(&query).unwrap());
+
+    assert_eq!(
+        as_text(json!({"$and": [{"number_field": {"$gt": 1}}, {"$or": [{"bool_field": true}]}]})),
+        "number_field > 1 and bool_field = true"
+    );
+    assert_eq!(
+        as_text(json!({
+            "$nor": [{"a": null}, {"b": {"$eq": 1.5}}],
+            "bike": {"$elemMatch": {"color": {"$in": ["orange"]}, "name": {"$ne": null}}},
+            "bike.name": {"$not": {"$regex": "x\\.y", "$options": "i"}},
+            "n": {"$nin": [1, 2], "$exists": true},
+            "t": {"$regex": "^a\\$b$"},
+        })),
+        concat!(
+            r#"a is not null and (not b = 1.5 or b is null) and "#,
+            r#"bike(color in ("orange") and name is not null) and "#,
+            r#"bike(not name contains_ignore_case "x.y" or name is null) and n is not null and "#,
+            r#"(not n in (1, 2) or n is null) and t = "a$b""#
+        )
+    );
+    // Negations match missing fields, unless they check for null
+    assert_eq!(
+        as_text(json!({"a": {"$not": {"$gt": 1, "$exists": false}}, "b": {"$ne": 2}})),
+        "(a is not null or not a > 1 or a is null) and (not b = 2 or b is null)"
+    );
+    assert_eq!(
+        as_text(json!({"$nor": [{"a": {"$ne": null}}, {"bike": {"$elemMatch": {"n": 1}}}]})),
+        "a is null and not bike.n = 1"
+    );
+
+    let error = |query: Json| decode::<FieldCondition>(&query).unwrap_err();
+
+    assert_eq!(
+        error(json!({"number_field": {"$foo": 1}})),
+        SyntaxError::at_path(
+            "/number_field/$foo",
+            SyntaxErrorKind::UnknownOperator("$foo".to_string())
+        )
+    );
+    assert_eq!(
+        error(json!({"$where": "this.a > 1"})),
+        SyntaxError::at_path(
+            "/$where",
+            SyntaxErrorKind::Unsupported("$where".to_string())
+        )
+    );
+    assert_eq!(
+        error(json!({"t": {"$regex": "^a.*b", "$options": "i"}})).to_string(),
+        "'$regex' is not supported at '/t/$regex'"
+    );
+    // LIKE is case insensitive in SQLite
+    assert_eq!(
+        error(json!({"t": {"$regex": "^ab"}})).to_string(),
+        "'case sensitive $regex' is not supported at '/t/$regex'"
+    );
+    assert_eq!(
+        error(json!({"$or": [{"a": 1}, {"n": {"$in": [1, null]}}]})),
+        SyntaxError::at_path(
+            "/$or/1/n/$in/1",
+            SyntaxErrorKind::UnexpectedToken("null".to_string())
+        )
+    );
+    assert_eq!(
+        error(json!({"a/b": {"$in": 1}})).to_string(),
+        "unexpected '1' at '/a~1b/$in'"
+    );
+}
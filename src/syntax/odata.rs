@@ -0,0 +1,261 @@
+/*
+    OData $filter expressions, i.e.
+    number_field ge 2 and (contains(text_field, 'abc') or bool_field eq true) and double_field eq null
+    Navigation properties map to inner statement conditions, bike/color eq 'orange'
+*/
+
+use super::*;
+
+// Arithmetic and enum flags operators, that can't be mapped to filters
+const UNSUPPORTED_OPERATORS: [&str; 6] = ["add", "sub", "mul", "div", "mod", "has"];
+
+fn tokenize(input: &str) -> Result<Vec<Lexed>, SyntaxError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((start, character)) = chars.next() {
+        let error = |kind| Err(SyntaxError::new(Some(start), kind));
+
+        let token = match character {
+            character if character.is_whitespace() => continue,
+            character if character.is_alphabetic() || character == '_' => {
+                let mut ident = character.to_string();
+                while let Some((_, character)) =
+                    chars.next_if(|(_, character)| character.is_alphanumeric() || *character == '_')
+                {
+                    ident.push(character);
+                }
+                Token::Ident(ident)
+            }
+            // Numbers and date/time literals (2026-10-18, 12:00:00, 2026-10-18T12:00:00Z)
+            character
+                if character.is_ascii_digit()
+                    || (character == '-'
+                        && chars.peek().is_some_and(|(_, next)| next.is_ascii_digit())) =>
+            {
+                let mut literal = character.to_string();
+                while let Some((_, character)) = chars.next_if(|(_, character)| {
+                    character.is_ascii_alphanumeric() || matches!(character, '-' | ':' | '.')
+                }) {
+                    literal.push(character);
+                }
+                if let Ok(integer) = literal.parse() {
+                    Token::Integer(integer)
+                } else if let Ok(float) = literal.parse() {
+                    Token::Float(float)
+                } else if literal.contains(['-', ':']) {
+                    Token::String(literal.trim_end_matches('Z').to_string())
+                } else {
+                    return error(SyntaxErrorKind::InvalidNumber(literal));
+                }
+            }
+            // Quote is escaped by doubling it
+            '\'' => {
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) if chars.next_if(|(_, next)| *next == '\'').is_some() => {
+                            string.push('\'')
+                        }
+                        Some((_, '\'')) => break,
+                        Some((_, character)) => string.push(character),
+                        None => return error(SyntaxErrorKind::UnterminatedString),
+                    }
+                }
+                Token::String(string)
+            }
+            '(' => Token::Symbol("("),
+            ')' => Token::Symbol(")"),
+            ',' => Token::Symbol(","),
+            '/' => Token::Symbol("/"),
+            ':' => Token::Symbol(":"),
+            character => return error(SyntaxErrorKind::UnexpectedCharacter(character)),
+        };
+
+        let end = chars.peek().map_or(input.len(), |(end, _)| *end);
+        tokens.push(Lexed { token, start, end });
+    }
+
+    Ok(tokens)
+}
+
+// Marker for OData parse methods
+struct OData;
+
+// Comparison or function call
+impl ConditionSyntax for OData {
+    fn parse_condition(parser: &mut Parser<'_, Self>) -> Result<SyntaxTree, SyntaxError> {
+        parser.parse_comparison()
+    }
+
+    fn parse_value(parser: &mut Parser<'_, Self>) -> Result<Value, SyntaxError> {
+        parser.parse_value()
+    }
+}
+
+impl Parser<'_, OData> {
+    fn unsupported(&self, position: usize, construct: &str) -> SyntaxError {
+        SyntaxError::new(
+            Some(position),
+            SyntaxErrorKind::Unsupported(construct.to_string()),
+        )
+    }
+
+    fn is_symbol_at(&self, offset: usize, symbol: &str) -> bool {
+        matches!(
+            self.tokens.get(self.index + offset),
+            Some(Lexed { token: Token::Symbol(current), .. }) if *current == symbol
+        )
+    }
+
+    fn parse_comparison(&mut self) -> Result<SyntaxTree, SyntaxError> {
+        // Function call, i.e. contains(text_field, 'abc')
+        if self.is_symbol_at(1, "(") {
+            let (function, position) = self.expect_ident()?;
+            let operator = match function.to_lowercase().as_str() {
+                "contains" => Operator::Contains,
+                "startswith" => Operator::StartsWith,
+                "endswith" => Operator::EndsWith,
+                _ => return Err(self.unsupported(position, &function)),
+            };
+            self.expect_symbol("(")?;
+            let path = self.parse_path()?;
+            self.expect_symbol(",")?;
+            let value = self.parse_value()?;
+            self.expect_symbol(")")?;
+            return Ok(into_tree(path, FieldFilter::Comparison(operator, value)));
+        }
+
+        let path = self.parse_path()?;
+        let position = self.position();
+        let (operator, _) = self.expect_ident()?;
+        let operator = match operator.as_str() {
+            "eq" => Operator::Equal,
+            "ne" => Operator::NotEqual,
+            "gt" => Operator::GreaterThan,
+            "ge" => Operator::GreaterOrEqual,
+            "lt" => Operator::LowerThan,
+            "le" => Operator::LowerOrEqual,
+            "in" => Operator::In,
+            operator if UNSUPPORTED_OPERATORS.contains(&operator) => {
+                return Err(self.unsupported(position, operator))
+            }
+            _ => return Err(self.unexpected_previous()),
+        };
+
+        let value = match operator {
+            Operator::In => self.parse_list()?,
+            _ => self.parse_value()?,
+        };
+        let (operator, value) = match (operator, value) {
+            (Operator::Equal, Value::Null) => (Operator::IsNull, Value::Null),
+            (Operator::NotEqual, Value::Null) => (Operator::IsNotNull, Value::Null),
+            (_, Value::Null) => return Err(self.unsupported(position, "null comparison")),
+            comparison => comparison,
+        };
+
+        Ok(into_tree(path, FieldFilter::Comparison(operator, value)))
+    }
+
+    // Navigation path, bike/color
+    fn parse_path(&mut self) -> Result<Vec<(String, usize)>, SyntaxError> {
+        let mut path = vec![self.expect_ident()?];
+        while self.eat_symbol("/") {
+            let (segment, position) = self.expect_ident()?;
+            // Lambda operators, bike/any(b: b/color eq 'orange')
+            if self.is_symbol_at(0, "(") {
+                return Err(self.unsupported(position, &segment));
+            }
+            path.push((segment, position));
+        }
+        Ok(path)
+    }
+
+    fn parse_value(&mut self) -> Result<Value, SyntaxError> {
+        Ok(match self.next()? {
+            Token::String(value) => Value::String(value),
+            Token::Integer(value) => Value::Integer(value),
+            Token::Float(value) => Value::Float(value),
+            Token::Ident(ident) if ident == "true" => Value::Bool(true),
+            Token::Ident(ident) if ident == "false" => Value::Bool(false),
+            Token::Ident(ident) if ident == "null" => Value::Null,
+            _ => return Err(self.unexpected_previous()),
+        })
+    }
+}
+
+// Path segments before the last one are inner statement fields
+fn into_tree(path: Vec<(String, usize)>, filter: FieldFilter) -> SyntaxTree {
+    path.into_iter()
+        .rev()
+        .fold(None, |inner: Option<SyntaxTree>, (field, position)| {
+            let filter = match inner {
+                Some(inner) => FieldFilter::Relation(vec![inner]),
+                None => filter.clone(),
+            };
+            Some(ConditionTree::Field(FieldCondition {
+                field,
+                location: Some(Location::Offset(position)),
+                filter,
+            }))
+        })
+        .unwrap()
+}
+
+pub(crate) fn parse<F: FieldRegistry>(input: &str) -> Result<Vec<ConditionTree<F>>, SyntaxError> {
+    Parser::<OData>::new(input, tokenize(input)?).parse_input()
+}
+
+#[test]
+fn test() {
+    let as_text = |input: &str| text::print(&parse::<FieldCondition>(input).unwrap());
+
+    assert_eq!(
+        as_text("number_field ge 2 and (contains(text_field, 'abc') or bool_field eq true) and double_field eq null"),
+        r#"number_field >= 2 and (text_field contains "abc" or bool_field = true) and double_field is null"#
+    );
+    assert_eq!(
+        as_text("not startswith(name, 'O''Brien') or bike/color in ('orange', 'purple')"),
+        r#"not name starts_with "O'Brien" or bike.color in ("orange", "purple")"#
+    );
+    assert_eq!(
+        as_text(
+            "date_field lt 2026-10-18 and timestamp_field gt 2026-10-18T12:00:00Z and n ne -1.5"
+        ),
+        r#"date_field < "2026-10-18" and timestamp_field > "2026-10-18T12:00:00" and n != -1.5"#
+    );
+
+    let error = |input: &str| parse::<FieldCondition>(input).unwrap_err();
+
+    assert_eq!(
+        error("number_field eq 1 and length(text_field) eq 3"),
+        SyntaxError::new(Some(22), SyntaxErrorKind::Unsupported("length".to_string()))
+    );
+    assert_eq!(
+        error("bike/any(b: b/color eq 'orange')"),
+        SyntaxError::new(Some(5), SyntaxErrorKind::Unsupported("any".to_string()))
+    );
+    assert_eq!(
+        error("number_field add 1 eq 2").to_string(),
+        "'add' is not supported at position 13"
+    );
+    assert_eq!(
+        error("number_field gt null"),
+        SyntaxError::new(
+            Some(13),
+            SyntaxErrorKind::Unsupported("null comparison".to_string())
+        )
+    );
+    assert_eq!(
+        error("text_field eq 'abc"),
+        SyntaxError::new(Some(14), SyntaxErrorKind::UnterminatedString)
+    );
+    assert_eq!(
+        error("number_field eq 1 and"),
+        SyntaxError::new(Some(21), SyntaxErrorKind::UnexpectedEnd)
+    );
+    assert_eq!(
+        error(&format!("{}n eq 1", "not (".repeat(100_000))).kind,
+        SyntaxErrorKind::TooDeep
+    );
+}
@@ -0,0 +1,591 @@
+/*
+    URL query string filters, i.e.
+    number_field[gte]=2&text_field[contains]=abc&or[0][bool_field]=true
+    Parameters are conditions of top level And, field without operator is compared with eq,
+    groups are lists (and[0], or[1]), inner statement conditions are nested under field
+    (bike[color][in][0]=orange), values are typed by field filter when mapped to Condition
+*/
+
+use std::collections::HashSet;
+
+use super::*;
+
+// Parameters nested by their bracketed keys, keys can repeat (i.e. a[eq]=1&a[eq]=2)
+enum Node {
+    Leaf {
+        value: String,
+        position: usize,
+    },
+    Map {
+        entries: Vec<(String, Node)>,
+        position: usize,
+    },
+}
+
+impl Node {
+    fn position(&self) -> usize {
+        match self {
+            Node::Leaf { position, .. } | Node::Map { position, .. } => *position,
+        }
+    }
+
+    fn insert(entries: &mut Vec<(String, Node)>, path: &[String], value: String, position: usize) {
+        let (key, path) = path.split_first().unwrap();
+
+        if path.is_empty() {
+            entries.push((key.clone(), Node::Leaf { value, position }));
+            return;
+        }
+
+        let existing = entries
+            .iter_mut()
+            .rev()
+            .find(|(existing_key, node)| existing_key == key && matches!(node, Node::Map { .. }));
+        match existing {
+            Some((_, Node::Map { entries, .. })) => Node::insert(entries, path, value, position),
+            _ => {
+                let mut entries_for_key = Vec::new();
+                Node::insert(&mut entries_for_key, path, value, position);
+                entries.push((
+                    key.clone(),
+                    Node::Map {
+                        entries: entries_for_key,
+                        position,
+                    },
+                ));
+            }
+        }
+    }
+
+    // Error for malformed structure, pointing to the value or first key
+    fn unexpected(&self) -> SyntaxError {
+        let token = match self {
+            Node::Leaf { value, .. } => value.clone(),
+            Node::Map { entries, .. } => entries[0].0.clone(),
+        };
+        SyntaxError::new(
+            Some(self.position()),
+            SyntaxErrorKind::UnexpectedToken(token),
+        )
+    }
+}
+
+fn percent_decode(raw: &str, position: usize) -> Result<String, SyntaxError> {
+    let invalid = |index: usize, token: &str| {
+        SyntaxError::new(
+            Some(position + index),
+            SyntaxErrorKind::UnexpectedToken(token.to_string()),
+        )
+    };
+
+    let mut bytes = Vec::new();
+    let mut index = 0;
+    while index < raw.len() {
+        match raw.as_bytes()[index] {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let escape = raw.get(index..index + 3).unwrap_or(&raw[index..]);
+                let byte = escape
+                    .get(1..)
+                    .filter(|hex| hex.len() == 2)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| invalid(index, escape))?;
+                bytes.push(byte);
+                index += 2;
+            }
+            byte => bytes.push(byte),
+        }
+        index += 1;
+    }
+
+    String::from_utf8(bytes).map_err(|_| invalid(0, raw))
+}
+
+fn percent_encode(value: &str, is_key: bool) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            // Keep brackets readable in keys
+            b'[' | b']' if is_key => encoded.push(byte as char),
+            byte => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+// field[op][0] -> ["field", "op", "0"], with at most MAX_DEPTH brackets, as inserting and
+// decoding nest once per bracket (not[not].. or bike[bike].. would overflow the stack)
+fn parse_key(key: &str, position: usize) -> Result<Vec<String>, SyntaxError> {
+    let unexpected = |token: &str| {
+        SyntaxError::new(
+            Some(position),
+            SyntaxErrorKind::UnexpectedToken(token.to_string()),
+        )
+    };
+
+    let (name, mut rest) = key.split_at(key.find('[').unwrap_or(key.len()));
+    if name.is_empty() {
+        return Err(unexpected(key));
+    }
+
+    let mut path = vec![name.to_string()];
+    while !rest.is_empty() {
+        if path.len() > MAX_DEPTH {
+            return Err(SyntaxError::new(Some(position), SyntaxErrorKind::TooDeep));
+        }
+        let end = match (rest.strip_prefix('['), rest.find(']')) {
+            (Some(_), Some(end)) => end,
+            _ => return Err(unexpected(rest)),
+        };
+        path.push(rest[1..end].to_string());
+        rest = &rest[end + 1..];
+    }
+
+    Ok(path)
+}
+
+// Indexed list (0, 1, ..) or appended ([]) items
+fn list_items(entries: Vec<(String, Node)>) -> Result<Vec<Node>, SyntaxError> {
+    let mut items = entries
+        .into_iter()
+        .map(|(key, node)| match key.as_str() {
+            "" => Ok((usize::MAX, node)),
+            index => match index.parse() {
+                Ok(index) => Ok((index, node)),
+                Err(_) => Err(SyntaxError::new(
+                    Some(node.position()),
+                    SyntaxErrorKind::UnexpectedToken(key),
+                )),
+            },
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    items.sort_by_key(|(index, _)| *index);
+    Ok(items.into_iter().map(|(_, node)| node).collect())
+}
+
+fn decode_conditions(entries: Vec<(String, Node)>) -> Result<Vec<SyntaxTree>, SyntaxError> {
+    let mut trees = Vec::new();
+    for (key, node) in entries {
+        match key.as_str() {
+            "and" => trees.push(ConditionTree::And(decode_list(node)?)),
+            "or" => trees.push(ConditionTree::Or(decode_list(node)?)),
+            "not" => trees.push(ConditionTree::Not(Box::new(decode_condition(node)?))),
+            _ => trees.extend(decode_field(key, node)?),
+        }
+    }
+    Ok(trees)
+}
+
+// Group item or negated condition, constant or conditions (multiple are And)
+fn decode_condition(node: Node) -> Result<SyntaxTree, SyntaxError> {
+    match node {
+        Node::Leaf { value, .. } if value == "true" => Ok(ConditionTree::True),
+        Node::Leaf { value, .. } if value == "false" => Ok(ConditionTree::False),
+        Node::Map { entries, .. } => {
+            let mut trees = decode_conditions(entries)?;
+            Ok(match trees.len() {
+                1 => trees.pop().unwrap(),
+                _ => ConditionTree::And(trees),
+            })
+        }
+        node => Err(node.unexpected()),
+    }
+}
+
+fn decode_list(node: Node) -> Result<Vec<SyntaxTree>, SyntaxError> {
+    match node {
+        Node::Leaf { value, .. } if value.is_empty() => Ok(Vec::new()),
+        Node::Map { entries, .. } => list_items(entries)?
+            .into_iter()
+            .map(decode_condition)
+            .collect(),
+        node => Err(node.unexpected()),
+    }
+}
+
+fn decode_field(field: String, node: Node) -> Result<Vec<SyntaxTree>, SyntaxError> {
+    let condition = |position, filter| {
+        ConditionTree::Field(FieldCondition {
+            field: field.clone(),
+            location: Some(Location::Offset(position)),
+            filter,
+        })
+    };
+
+    match node {
+        Node::Leaf { value, position } => Ok(vec![condition(
+            position,
+            FieldFilter::Comparison(Operator::Equal, Value::String(value)),
+        )]),
+        // Operators, or inner statement conditions if none of the keys is an operator
+        Node::Map { entries, .. }
+            if entries
+                .iter()
+                .any(|(key, _)| Operator::from_name(key).is_some()) =>
+        {
+            entries
+                .into_iter()
+                .map(|(key, node)| {
+                    let position = node.position();
+                    let operator = Operator::from_name(&key).ok_or_else(|| {
+                        SyntaxError::new(Some(position), SyntaxErrorKind::UnknownOperator(key))
+                    })?;
+                    let value = decode_value(operator, node)?;
+                    Ok(condition(
+                        position,
+                        FieldFilter::Comparison(operator, value),
+                    ))
+                })
+                .collect()
+        }
+        Node::Map { entries, position } => Ok(vec![condition(
+            position,
+            FieldFilter::Relation(decode_conditions(entries)?),
+        )]),
+    }
+}
+
+fn decode_value(operator: Operator, node: Node) -> Result<Value, SyntaxError> {
+    let value = match (operator, node) {
+        (Operator::IsNull | Operator::IsNotNull, Node::Leaf { value, .. })
+            if value.is_empty() || value == "true" =>
+        {
+            Value::Null
+        }
+        (Operator::In | Operator::NotIn | Operator::InIgnoreCase, Node::Leaf { value, .. })
+            if value.is_empty() =>
+        {
+            Value::List(Vec::new())
+        }
+        (Operator::In | Operator::NotIn | Operator::InIgnoreCase, Node::Map { entries, .. }) => {
+            Value::List(
+                list_items(entries)?
+                    .into_iter()
+                    .map(|node| match node {
+                        Node::Leaf { value, .. } => Ok(Value::String(value)),
+                        node => Err(node.unexpected()),
+                    })
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        (Operator::Between, Node::Map { entries, position }) => {
+            let (mut from, mut to, mut from_inclusive, mut to_inclusive) = (None, None, true, true);
+            for (key, node) in entries {
+                let value = match node {
+                    Node::Leaf { value, .. } => value,
+                    node => return Err(node.unexpected()),
+                };
+                let inclusive = || value.parse::<bool>();
+                match key.as_str() {
+                    "from" => from = Some(value),
+                    "to" => to = Some(value),
+                    "from_inclusive" if inclusive().is_ok() => {
+                        from_inclusive = inclusive().unwrap()
+                    }
+                    "to_inclusive" if inclusive().is_ok() => to_inclusive = inclusive().unwrap(),
+                    _ => {
+                        return Err(SyntaxError::new(
+                            Some(position),
+                            SyntaxErrorKind::UnexpectedToken(key),
+                        ))
+                    }
+                }
+            }
+            match (from, to) {
+                (Some(from), Some(to)) => Value::Range {
+                    from: Box::new(Value::String(from)),
+                    to: Box::new(Value::String(to)),
+                    from_inclusive,
+                    to_inclusive,
+                },
+                _ => {
+                    return Err(SyntaxError::new(
+                        Some(position),
+                        SyntaxErrorKind::UnexpectedEnd,
+                    ))
+                }
+            }
+        }
+        // [number, unit], same as JSON
+        (Operator::Last | Operator::OlderThan, Node::Map { entries, position }) => {
+            let items = list_items(entries)?;
+            let duration = match items.as_slice() {
+                [Node::Leaf { value: number, .. }, Node::Leaf { value: unit, .. }] => {
+                    number.parse().ok().zip(TimeUnit::from_name(unit))
+                }
+                _ => None,
+            };
+            match duration {
+                Some((number, unit)) => Value::Duration(number, unit),
+                None => {
+                    return Err(SyntaxError::new(
+                        Some(position),
+                        SyntaxErrorKind::UnexpectedToken(operator.name().to_string()),
+                    ))
+                }
+            }
+        }
+        (Operator::This, Node::Leaf { value, position }) => match Period::from_name(&value) {
+            Some(period) => Value::Period(period),
+            None => {
+                return Err(SyntaxError::new(
+                    Some(position),
+                    SyntaxErrorKind::UnexpectedToken(value),
+                ))
+            }
+        },
+        (
+            Operator::IsNull
+            | Operator::IsNotNull
+            | Operator::In
+            | Operator::NotIn
+            | Operator::InIgnoreCase
+            | Operator::Between
+            | Operator::Last
+            | Operator::OlderThan
+            | Operator::This,
+            node,
+        ) => return Err(node.unexpected()),
+        (_, Node::Leaf { value, .. }) => Value::String(value),
+        (_, node) => return Err(node.unexpected()),
+    };
+    Ok(value)
+}
+
+pub(crate) fn decode<F: FieldRegistry>(
+    query_string: &str,
+) -> Result<Vec<ConditionTree<F>>, SyntaxError> {
+    let (query_string, offset) = match query_string.strip_prefix('?') {
+        Some(query_string) => (query_string, 1),
+        None => (query_string, 0),
+    };
+
+    let mut entries = Vec::new();
+    let mut position = offset;
+    for parameter in query_string.split('&') {
+        if !parameter.is_empty() {
+            let (key, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+            let value = percent_decode(value, position + key.len() + 1)?;
+            let key = percent_decode(key, position)?;
+            Node::insert(&mut entries, &parse_key(&key, position)?, value, position);
+        }
+        position += parameter.len() + 1;
+    }
+
+    decode_conditions(entries)?
+        .into_iter()
+        .map(ConditionTree::from_syntax_tree)
+        .collect()
+}
+
+pub(crate) fn encode<F: FieldRegistry>(conditions: &[ConditionTree<F>]) -> String {
+    let trees: Vec<SyntaxTree> = conditions
+        .iter()
+        .map(ConditionTree::to_syntax_tree)
+        .collect();
+    let mut parameters = Vec::new();
+    encode_conditions(&trees, "", &mut parameters);
+
+    parameters
+        .into_iter()
+        .map(|(key, value)| {
+            format!(
+                "{}={}",
+                percent_encode(&key, true),
+                percent_encode(&value, false)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn key(prefix: &str, key: &str) -> String {
+    match prefix {
+        "" => key.to_string(),
+        prefix => format!("{prefix}[{key}]"),
+    }
+}
+
+fn flatten_and<'a>(trees: &'a [SyntaxTree], flattened: &mut Vec<&'a SyntaxTree>) {
+    for tree in trees {
+        match tree {
+            ConditionTree::And(trees) => flatten_and(trees, flattened),
+            tree => flattened.push(tree),
+        }
+    }
+}
+
+// Conditions under the same prefix, first condition for every key is encoded in place, repeated
+// keys (which would be merged when decoded) and constants are moved to and[..] group
+fn encode_conditions(trees: &[SyntaxTree], prefix: &str, parameters: &mut Vec<(String, String)>) {
+    let mut flattened = Vec::new();
+    flatten_and(trees, &mut flattened);
+
+    let mut used_keys = HashSet::new();
+    let mut grouped = Vec::new();
+    for tree in flattened {
+        let tree_key = match tree {
+            ConditionTree::Or(trees) if !trees.is_empty() => Some("or"),
+            ConditionTree::Not(_) => Some("not"),
+            ConditionTree::Field(condition) => Some(condition.field.as_str()),
+            _ => None,
+        };
+        match tree_key {
+            Some(tree_key) if used_keys.insert(tree_key) => {
+                encode_tree(tree, &key(prefix, tree_key), parameters)
+            }
+            _ => grouped.push(tree),
+        }
+    }
+
+    for (index, tree) in grouped.into_iter().enumerate() {
+        encode_condition(
+            tree,
+            &key(&key(prefix, "and"), &index.to_string()),
+            parameters,
+        );
+    }
+}
+
+// Or, Not or field at it's key
+fn encode_tree(tree: &SyntaxTree, prefix: &str, parameters: &mut Vec<(String, String)>) {
+    match tree {
+        ConditionTree::Or(trees) => {
+            for (index, tree) in trees.iter().enumerate() {
+                encode_condition(tree, &key(prefix, &index.to_string()), parameters);
+            }
+        }
+        ConditionTree::Not(tree) => encode_condition(tree, prefix, parameters),
+        ConditionTree::Field(condition) => encode_field(&condition.filter, prefix, parameters),
+        _ => unreachable!("only keyed conditions are encoded in place"),
+    }
+}
+
+// Group item or negated condition
+fn encode_condition(tree: &SyntaxTree, prefix: &str, parameters: &mut Vec<(String, String)>) {
+    let constant = match tree {
+        ConditionTree::True => "true",
+        ConditionTree::False => "false",
+        ConditionTree::And(trees) if trees.is_empty() => "true",
+        ConditionTree::Or(trees) if trees.is_empty() => "false",
+        tree => return encode_conditions(std::slice::from_ref(tree), prefix, parameters),
+    };
+    parameters.push((prefix.to_string(), constant.to_string()));
+}
+
+fn encode_field(filter: &FieldFilter, prefix: &str, parameters: &mut Vec<(String, String)>) {
+    let (operator, value) = match filter {
+        FieldFilter::Relation(trees) | FieldFilter::Exists(trees) => {
+            let count = parameters.len();
+            encode_conditions(trees, prefix, parameters);
+            // Empty inner statement conditions
+            if parameters.len() == count {
+                parameters.push((key(&key(prefix, "and"), "0"), "true".to_string()));
+            }
+            return;
+        }
+        FieldFilter::Comparison(operator, value) => (operator, value),
+    };
+
+    let prefix = key(prefix, operator.name());
+    let mut push = |key: String, value: String| parameters.push((key, value));
+
+    match value {
+        Value::Null => push(prefix, String::new()),
+        Value::List(values) if values.is_empty() => push(prefix, String::new()),
+        Value::List(values) => {
+            for (index, value) in values.iter().enumerate() {
+                push(key(&prefix, &index.to_string()), encode_value(value));
+            }
+        }
+        Value::Range {
+            from,
+            to,
+            from_inclusive,
+            to_inclusive,
+        } => {
+            push(key(&prefix, "from"), encode_value(from));
+            push(key(&prefix, "to"), encode_value(to));
+            if !from_inclusive {
+                push(key(&prefix, "from_inclusive"), "false".to_string());
+            }
+            if !to_inclusive {
+                push(key(&prefix, "to_inclusive"), "false".to_string());
+            }
+        }
+        Value::Duration(number, unit) => {
+            push(key(&prefix, "0"), number.to_string());
+            push(key(&prefix, "1"), unit.name().to_string());
+        }
+        value => push(prefix, encode_value(value)),
+    }
+}
+
+fn encode_value(value: &Value) -> String {
+    match value {
+        Value::Bool(value) => value.to_string(),
+        Value::Integer(value) => value.to_string(),
+        Value::Float(value) => format!("{value:?}"),
+        Value::String(value) => value.clone(),
+        Value::Period(period) => period.name().to_string(),
+        // Nested values are not produced by filters
+        _ => String::new(),
+    }
+}
+
+#[test]
+fn test() {
+    let decode = decode::<FieldCondition>;
+
+    // Encoded back the same way
+    for query_string in [
+        "number_field[gte]=2&text_field[contains]=abc&or[0][bool_field][eq]=true&or[1][a][is_null]=",
+        "a[in][0]=x&a[in][1]=y%26z&not[b][between][from]=1&not[b][between][to]=2.5&b[last][0]=7&b[last][1]=days",
+        "bike[color][in][0]=orange&bike[color][in][1]=purple&bike[or][0][name][eq]=a%20b&bike[or][1]=false",
+        "a[eq]=1&and[0][a][eq]=2&and[1]=true",
+    ] {
+        assert_eq!(encode(&decode(query_string).unwrap()), query_string);
+    }
+
+    // Normalised
+    assert_eq!(
+        encode(&decode("?a=1&a[in][]=2&a[in][]=3&b[between][from]=1&b[between][to]=5&b[between][to_inclusive]=false&or=&c+d%5Beq%5D=e+f").unwrap()),
+        "a[eq]=1&b[between][from]=1&b[between][to]=5&b[between][to_inclusive]=false&c%20d[eq]=e%20f&and[0][a][in][0]=2&and[0][a][in][1]=3&and[1]=false"
+    );
+    assert_eq!(decode("").unwrap(), vec![]);
+
+    let error = |query_string: &str| decode(query_string).unwrap_err();
+
+    assert_eq!(
+        error("a[eq]=1&b[gte]=1&b[foo]=2"),
+        SyntaxError::new(
+            Some(17),
+            SyntaxErrorKind::UnknownOperator("foo".to_string())
+        )
+    );
+    assert_eq!(
+        error("a[eq]=1&b[eq=2"),
+        SyntaxError::new(Some(8), SyntaxErrorKind::UnexpectedToken("[eq".to_string()))
+    );
+    assert_eq!(
+        error("a[eq]=%zz"),
+        SyntaxError::new(Some(6), SyntaxErrorKind::UnexpectedToken("%zz".to_string()))
+    );
+    assert_eq!(
+        error("or[x][a]=1").to_string(),
+        "unexpected 'x' at position 0"
+    );
+    assert_eq!(
+        error(&format!("a[eq]=1&not{}[a]=1", "[not]".repeat(100_000))),
+        SyntaxError::new(Some(8), SyntaxErrorKind::TooDeep)
+    );
+    assert_eq!(
+        error(&format!("a{}=1", "[a]".repeat(MAX_DEPTH + 1))).to_string(),
+        "nesting is deeper than 64 at position 0"
+    );
+    let nested = format!("a{}[eq]=1", "[a]".repeat(MAX_DEPTH - 1));
+    assert_eq!(encode(&decode(&nested).unwrap()), nested);
+}
@@ -0,0 +1,286 @@
+/*
+    RSQL/FIQL expressions, i.e.
+    name==c*;color=in=(orange,purple),number_field=gt=3
+    ; (and) binds stronger then , (or), with and, or, not, brackets and true/false of text syntax.
+    == and != with * wildcards aren't a LIKE of the value as is (% and _ in it would be wildcards
+    too), x*, *x and *x* are starts with, ends with and contains, and other placements are wildcard
+    filters where only * matches any characters. Dotted selectors are inner statement conditions
+    (bike.color==grey)
+*/
+
+use super::*;
+
+const COMPARATORS: [&str; 13] = [
+    "==", "!=", "<", "<=", ">", ">=", "=lt=", "=le=", "=gt=", "=ge=", "=in=", "=out=", "=isnull=",
+];
+
+// Characters that end unquoted selectors and values
+fn is_reserved(character: char) -> bool {
+    character.is_whitespace() || "\"'();,=!<>".contains(character)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Lexed>, SyntaxError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((start, character)) = chars.next() {
+        let error = |kind| Err(SyntaxError::new(Some(start), kind));
+
+        let token = match character {
+            character if character.is_whitespace() => continue,
+            quote @ ('"' | '\'') => {
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, character)) if character == quote => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, character)) => string.push(character),
+                            None => return error(SyntaxErrorKind::UnterminatedString),
+                        },
+                        Some((_, character)) => string.push(character),
+                        None => return error(SyntaxErrorKind::UnterminatedString),
+                    }
+                }
+                Token::String(string)
+            }
+            '(' => Token::Symbol("("),
+            ')' => Token::Symbol(")"),
+            ';' => Token::Symbol(";"),
+            ',' => Token::Symbol(","),
+            // ==, !=, <, <=, >, >= and =name=
+            '=' | '!' | '<' | '>' => {
+                let mut comparator = character.to_string();
+                if character == '=' && chars.peek().is_some_and(|(_, next)| *next != '=') {
+                    while let Some((_, character)) =
+                        chars.next_if(|(_, character)| character.is_ascii_alphabetic())
+                    {
+                        comparator.push(character);
+                    }
+                }
+                if let Some((_, character)) = chars.next_if(|(_, next)| *next == '=') {
+                    comparator.push(character);
+                }
+                match COMPARATORS.iter().find(|known| **known == comparator) {
+                    Some(comparator) => Token::Symbol(comparator),
+                    None => return error(SyntaxErrorKind::UnknownOperator(comparator)),
+                }
+            }
+            character => {
+                let mut unquoted = character.to_string();
+                while let Some((_, character)) = chars.next_if(|(_, next)| !is_reserved(*next)) {
+                    unquoted.push(character);
+                }
+                Token::Ident(unquoted)
+            }
+        };
+
+        let end = chars.peek().map_or(input.len(), |(end, _)| *end);
+        tokens.push(Lexed { token, start, end });
+    }
+
+    Ok(tokens)
+}
+
+// Marker for RSQL parse methods
+struct Rsql;
+
+impl ConditionSyntax for Rsql {
+    const OR_SYMBOL: Option<&'static str> = Some(",");
+    const AND_SYMBOL: Option<&'static str> = Some(";");
+
+    fn parse_condition(parser: &mut Parser<'_, Self>) -> Result<SyntaxTree, SyntaxError> {
+        parser.parse_constraint()
+    }
+
+    fn parse_value(parser: &mut Parser<'_, Self>) -> Result<Value, SyntaxError> {
+        Ok(Value::String(parser.parse_argument()?))
+    }
+}
+
+impl Parser<'_, Rsql> {
+    fn parse_constraint(&mut self) -> Result<SyntaxTree, SyntaxError> {
+        let (selector, position) = self.expect_ident()?;
+        let comparator_position = self.position();
+        let comparator = match self.next()? {
+            Token::Symbol(comparator) if COMPARATORS.contains(&comparator) => comparator,
+            _ => return Err(self.unexpected_previous()),
+        };
+        let arguments = self.parse_arguments()?;
+        let single = || match arguments.as_slice() {
+            [value] => Ok(value.clone()),
+            _ => Err(SyntaxError::new(
+                Some(comparator_position),
+                SyntaxErrorKind::UnexpectedToken(comparator.to_string()),
+            )),
+        };
+
+        let (operator, value) = match comparator {
+            "==" => match single()? {
+                value if value.contains('*') => wildcard(value),
+                value => (Operator::Equal, value),
+            },
+            "!=" => match single()? {
+                value if value.contains('*') => (Operator::NotWildcard, value),
+                value => (Operator::NotEqual, value),
+            },
+            "=in=" | "=out=" => {
+                let operator = match comparator {
+                    "=in=" => Operator::In,
+                    _ => Operator::NotIn,
+                };
+                let values = arguments.into_iter().map(Value::String).collect();
+                return Ok(into_tree(
+                    &selector,
+                    position,
+                    FieldFilter::Comparison(operator, Value::List(values)),
+                ));
+            }
+            "=isnull=" => {
+                let operator = match single()?.as_str() {
+                    "true" => Operator::IsNull,
+                    "false" => Operator::IsNotNull,
+                    value => {
+                        return Err(SyntaxError::new(
+                            Some(comparator_position),
+                            SyntaxErrorKind::UnexpectedToken(value.to_string()),
+                        ))
+                    }
+                };
+                return Ok(into_tree(
+                    &selector,
+                    position,
+                    FieldFilter::Comparison(operator, Value::Null),
+                ));
+            }
+            "<" | "=lt=" => (Operator::LowerThan, single()?),
+            "<=" | "=le=" => (Operator::LowerOrEqual, single()?),
+            ">" | "=gt=" => (Operator::GreaterThan, single()?),
+            _ => (Operator::GreaterOrEqual, single()?),
+        };
+
+        Ok(into_tree(
+            &selector,
+            position,
+            FieldFilter::Comparison(operator, Value::String(value)),
+        ))
+    }
+
+    // Single value or list in brackets, values are typed by field
+    fn parse_arguments(&mut self) -> Result<Vec<String>, SyntaxError> {
+        if !self.eat_symbol("(") {
+            return Ok(vec![self.parse_argument()?]);
+        }
+        let mut values = vec![self.parse_argument()?];
+        while self.eat_symbol(",") {
+            values.push(self.parse_argument()?);
+        }
+        self.expect_symbol(")")?;
+        Ok(values)
+    }
+
+    fn parse_argument(&mut self) -> Result<String, SyntaxError> {
+        match self.next()? {
+            Token::Ident(value) | Token::String(value) => Ok(value),
+            _ => Err(self.unexpected_previous()),
+        }
+    }
+}
+
+// x*, *x and *x* are starts with, ends with and contains, other wildcards (i.e. a*b) are kept
+fn wildcard(value: String) -> (Operator, String) {
+    let literal = |value: &&str| !value.contains('*');
+
+    if let Some(inner) = value
+        .strip_prefix('*')
+        .and_then(|value| value.strip_suffix('*'))
+        .filter(literal)
+    {
+        return (Operator::Contains, inner.to_string());
+    }
+    if let Some(prefix) = value.strip_suffix('*').filter(literal) {
+        return (Operator::StartsWith, prefix.to_string());
+    }
+    if let Some(suffix) = value.strip_prefix('*').filter(literal) {
+        return (Operator::EndsWith, suffix.to_string());
+    }
+    (Operator::Wildcard, value)
+}
+
+// Dotted selector segments before the last one are inner statement fields
+fn into_tree(selector: &str, position: usize, filter: FieldFilter) -> SyntaxTree {
+    let mut segments = Vec::new();
+    let mut offset = position;
+    for segment in selector.split('.') {
+        segments.push((segment.to_string(), offset));
+        offset += segment.len() + 1;
+    }
+
+    segments
+        .into_iter()
+        .rev()
+        .fold(None, |inner: Option<SyntaxTree>, (field, position)| {
+            let filter = match inner {
+                Some(inner) => FieldFilter::Relation(vec![inner]),
+                None => filter.clone(),
+            };
+            Some(ConditionTree::Field(FieldCondition {
+                field,
+                location: Some(Location::Offset(position)),
+                filter,
+            }))
+        })
+        .unwrap()
+}
+
+pub(crate) fn parse<F: FieldRegistry>(input: &str) -> Result<Vec<ConditionTree<F>>, SyntaxError> {
+    Parser::<Rsql>::new(input, tokenize(input)?).parse_input()
+}
+
+#[test]
+fn test() {
+    let as_text = |input: &str| text::print(&parse::<FieldCondition>(input).unwrap());
+
+    assert_eq!(
+        as_text("name==c*;color=in=(orange,purple),number_field=gt=3"),
+        r#"name starts_with "c" and color in ("orange", "purple") or number_field > "3""#
+    );
+    // % and _ are literal
+    assert_eq!(
+        as_text("a==50%*;b==*a_b;c==*%*;d==a*b_c;e!=*o*"),
+        r#"a starts_with "50%" and b ends_with "a_b" and c contains "%" and d wildcard "a*b_c" and e not_wildcard "*o*""#
+    );
+    assert_eq!(
+        as_text(r#"name!="a b";(n<=1 or n>=5) and bike.color=out=grey;d=isnull=false"#),
+        r#"name != "a b" and (n <= "1" or n >= "5") and bike.color not in ("grey") and d is not null"#
+    );
+    assert_eq!(
+        as_text("not(a==1,b==2);true"),
+        r#"not (a = "1" or b = "2") and true"#
+    );
+
+    let error = |input: &str| parse::<FieldCondition>(input).unwrap_err();
+
+    assert_eq!(
+        error("name==c*;number_field=foo=3"),
+        SyntaxError::new(
+            Some(21),
+            SyntaxErrorKind::UnknownOperator("=foo=".to_string())
+        )
+    );
+    assert_eq!(
+        error("name==(a,b)"),
+        SyntaxError::new(Some(4), SyntaxErrorKind::UnexpectedToken("==".to_string()))
+    );
+    assert_eq!(
+        error("(name==a;n=gt=1"),
+        SyntaxError::new(Some(15), SyntaxErrorKind::UnexpectedEnd)
+    );
+    assert_eq!(
+        error("name==a;;n=gt=1").to_string(),
+        "unexpected ';' at position 8"
+    );
+    assert_eq!(
+        error(&"(".repeat(100_000)),
+        SyntaxError::new(Some(MAX_DEPTH), SyntaxErrorKind::TooDeep)
+    );
+}
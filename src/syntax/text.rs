@@ -0,0 +1,504 @@
+/*
+    Text filter expressions, i.e.
+    number_field >= 2 and (text_field like "4%" or bool_field = true) and double_field is null
+    Inner statement fields take conditions in brackets, bike(name = "c1" or color = "grey"),
+    or a path for single condition, bike.color in ("orange", "purple")
+*/
+
+use super::*;
+
+fn tokenize(input: &str) -> Result<Vec<Lexed>, SyntaxError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((start, character)) = chars.next() {
+        let error = |kind| Err(SyntaxError::new(Some(start), kind));
+
+        let token = match character {
+            character if character.is_whitespace() => continue,
+            character if character.is_alphabetic() || character == '_' => {
+                let mut ident = character.to_string();
+                while let Some((_, character)) =
+                    chars.next_if(|(_, character)| character.is_alphanumeric() || *character == '_')
+                {
+                    ident.push(character);
+                }
+                Token::Ident(ident)
+            }
+            // Negative infinity as printed, other numbers start with a digit
+            '-' if input[start..].starts_with("-inf")
+                && !input[start + 4..].starts_with(|character: char| {
+                    character.is_alphanumeric() || character == '_'
+                }) =>
+            {
+                chars.nth(2);
+                Token::Float(f64::NEG_INFINITY)
+            }
+            character
+                if character.is_ascii_digit()
+                    || (character == '-'
+                        && chars.peek().is_some_and(|(_, next)| next.is_ascii_digit())) =>
+            {
+                let mut number = character.to_string();
+                while let Some((_, character)) =
+                    chars.next_if(|(_, character)| character.is_ascii_digit() || *character == '.')
+                {
+                    number.push(character);
+                }
+                if let Ok(integer) = number.parse() {
+                    Token::Integer(integer)
+                } else if let Ok(float) = number.parse() {
+                    Token::Float(float)
+                } else {
+                    return error(SyntaxErrorKind::InvalidNumber(number));
+                }
+            }
+            quote @ ('"' | '\'') => {
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, character)) if character == quote => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, 'n')) => string.push('\n'),
+                            Some((_, 't')) => string.push('\t'),
+                            Some((_, character)) => string.push(character),
+                            None => return error(SyntaxErrorKind::UnterminatedString),
+                        },
+                        Some((_, character)) => string.push(character),
+                        None => return error(SyntaxErrorKind::UnterminatedString),
+                    }
+                }
+                Token::String(string)
+            }
+            '(' => Token::Symbol("("),
+            ')' => Token::Symbol(")"),
+            '[' => Token::Symbol("["),
+            ']' => Token::Symbol("]"),
+            ',' => Token::Symbol(","),
+            '.' => Token::Symbol("."),
+            '=' => Token::Symbol("="),
+            '!' if chars.next_if(|(_, next)| *next == '=').is_some() => Token::Symbol("!="),
+            '<' if chars.next_if(|(_, next)| *next == '=').is_some() => Token::Symbol("<="),
+            '<' if chars.next_if(|(_, next)| *next == '>').is_some() => Token::Symbol("<>"),
+            '<' => Token::Symbol("<"),
+            '>' if chars.next_if(|(_, next)| *next == '=').is_some() => Token::Symbol(">="),
+            '>' => Token::Symbol(">"),
+            character => return error(SyntaxErrorKind::UnexpectedCharacter(character)),
+        };
+
+        let end = chars.peek().map_or(input.len(), |(end, _)| *end);
+        tokens.push(Lexed { token, start, end });
+    }
+
+    Ok(tokens)
+}
+
+// Marker for text syntax parse methods
+struct Text;
+
+// Field condition, comparison or inner statement conditions
+impl ConditionSyntax for Text {
+    fn parse_condition(parser: &mut Parser<'_, Self>) -> Result<SyntaxTree, SyntaxError> {
+        let (field, position) = parser.expect_ident()?;
+        parser.parse_field(field, position)
+    }
+
+    fn parse_value(parser: &mut Parser<'_, Self>) -> Result<Value, SyntaxError> {
+        parser.parse_value()
+    }
+}
+
+impl Parser<'_, Text> {
+    fn parse_field(&mut self, field: String, position: usize) -> Result<SyntaxTree, SyntaxError> {
+        let filter = if self.eat_symbol(".") {
+            // Path is a relation with single condition
+            let (inner_field, inner_position) = self.expect_ident()?;
+            FieldFilter::Relation(vec![
+                self.nested(|parser| parser.parse_field(inner_field, inner_position))?
+            ])
+        } else if self.eat_symbol("(") {
+            let tree = self.parse_or()?;
+            self.expect_symbol(")")?;
+            FieldFilter::Relation(into_conditions(tree))
+        } else {
+            let (operator, value) = self.parse_comparison()?;
+            FieldFilter::Comparison(operator, value)
+        };
+
+        Ok(ConditionTree::Field(FieldCondition {
+            field,
+            location: Some(Location::Offset(position)),
+            filter,
+        }))
+    }
+
+    fn parse_comparison(&mut self) -> Result<(Operator, Value), SyntaxError> {
+        let operator = match self.next()? {
+            Token::Symbol("=") => Operator::Equal,
+            Token::Symbol("!=" | "<>") => Operator::NotEqual,
+            Token::Symbol("<") => Operator::LowerThan,
+            Token::Symbol("<=") => Operator::LowerOrEqual,
+            Token::Symbol(">") => Operator::GreaterThan,
+            Token::Symbol(">=") => Operator::GreaterOrEqual,
+            Token::Ident(ident) => match ident.to_lowercase().as_str() {
+                "is" => {
+                    let operator = match self.eat_keyword("not") {
+                        true => Operator::IsNotNull,
+                        false => Operator::IsNull,
+                    };
+                    self.expect_keyword("null")?;
+                    return Ok((operator, Value::Null));
+                }
+                "not" if self.eat_keyword("like") => Operator::NotLike,
+                "not" if self.eat_keyword("in") => Operator::NotIn,
+                "not" => return Err(self.unexpected()),
+                name => match Operator::from_name(name) {
+                    Some(Operator::IsNull | Operator::IsNotNull) | None => {
+                        return Err(self.unexpected_previous())
+                    }
+                    Some(operator) => operator,
+                },
+            },
+            _ => return Err(self.unexpected_previous()),
+        };
+
+        let value = match operator {
+            Operator::In | Operator::NotIn | Operator::InIgnoreCase => self.parse_list()?,
+            Operator::Between => self.parse_range()?,
+            Operator::Last | Operator::OlderThan => self.parse_duration()?,
+            Operator::This => self.parse_period()?,
+            _ => self.parse_value()?,
+        };
+
+        Ok((operator, value))
+    }
+
+    fn parse_value(&mut self) -> Result<Value, SyntaxError> {
+        Ok(match self.next()? {
+            Token::String(value) => Value::String(value),
+            Token::Integer(value) => Value::Integer(value),
+            Token::Float(value) => Value::Float(value),
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("true") => Value::Bool(true),
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("false") => Value::Bool(false),
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("nan") => Value::Float(f64::NAN),
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("inf") => Value::Float(f64::INFINITY),
+            _ => return Err(self.unexpected_previous()),
+        })
+    }
+
+    // Either inclusive `between 1 and 2` or interval `between [1, 2)`
+    fn parse_range(&mut self) -> Result<Value, SyntaxError> {
+        let (from, to, from_inclusive, to_inclusive) =
+            if self.eat_symbol("[") || self.eat_symbol("(") {
+                let from_inclusive = self.tokens[self.index - 1].token == Token::Symbol("[");
+                let from = self.parse_value()?;
+                self.expect_symbol(",")?;
+                let to = self.parse_value()?;
+                let to_inclusive = match self.next()? {
+                    Token::Symbol("]") => true,
+                    Token::Symbol(")") => false,
+                    _ => return Err(self.unexpected_previous()),
+                };
+                (from, to, from_inclusive, to_inclusive)
+            } else {
+                let from = self.parse_value()?;
+                self.expect_keyword("and")?;
+                (from, self.parse_value()?, true, true)
+            };
+
+        Ok(Value::Range {
+            from: Box::new(from),
+            to: Box::new(to),
+            from_inclusive,
+            to_inclusive,
+        })
+    }
+
+    fn parse_duration(&mut self) -> Result<Value, SyntaxError> {
+        let number = match self.next()? {
+            Token::Integer(number) => number.try_into().map_err(|_| self.unexpected_previous())?,
+            _ => return Err(self.unexpected_previous()),
+        };
+        let (unit, _) = self.expect_ident()?;
+        let unit = match TimeUnit::from_name(&unit.to_lowercase()) {
+            Some(unit) => unit,
+            None => return Err(self.unexpected_previous()),
+        };
+        Ok(Value::Duration(number, unit))
+    }
+
+    fn parse_period(&mut self) -> Result<Value, SyntaxError> {
+        let (period, _) = self.expect_ident()?;
+        let period = match Period::from_name(&period.to_lowercase()) {
+            Some(period) => period,
+            None => return Err(self.unexpected_previous()),
+        };
+        Ok(Value::Period(period))
+    }
+}
+
+pub(crate) fn parse<F: FieldRegistry>(input: &str) -> Result<Vec<ConditionTree<F>>, SyntaxError> {
+    Parser::<Text>::new(input, tokenize(input)?).parse_input()
+}
+
+pub(crate) fn print<F: FieldRegistry>(conditions: &[ConditionTree<F>]) -> String {
+    let trees: Vec<SyntaxTree> = conditions
+        .iter()
+        .map(ConditionTree::to_syntax_tree)
+        .collect();
+    print_conditions(&trees)
+}
+
+const OR_PRECEDENCE: u8 = 0;
+const AND_PRECEDENCE: u8 = 1;
+const NOT_PRECEDENCE: u8 = 2;
+const ATOM_PRECEDENCE: u8 = 3;
+
+fn print_conditions(trees: &[SyntaxTree]) -> String {
+    if let [tree] = trees {
+        return print_tree(tree, OR_PRECEDENCE);
+    }
+    trees
+        .iter()
+        .map(|tree| print_tree(tree, AND_PRECEDENCE))
+        .collect::<Vec<_>>()
+        .join(" and ")
+}
+
+// Brackets are only added when tree binds weaker then it's parent
+fn print_tree(tree: &SyntaxTree, parent_precedence: u8) -> String {
+    let (text, precedence) = match tree {
+        ConditionTree::And(trees) if trees.is_empty() => ("true".to_string(), ATOM_PRECEDENCE),
+        ConditionTree::Or(trees) if trees.is_empty() => ("false".to_string(), ATOM_PRECEDENCE),
+        // Single condition group is printed as the condition
+        ConditionTree::And(trees) | ConditionTree::Or(trees) if trees.len() == 1 => {
+            return print_tree(&trees[0], parent_precedence)
+        }
+        ConditionTree::And(trees) => (print_conditions(trees), AND_PRECEDENCE),
+        ConditionTree::Or(trees) => (
+            trees
+                .iter()
+                .map(|tree| print_tree(tree, OR_PRECEDENCE))
+                .collect::<Vec<_>>()
+                .join(" or "),
+            OR_PRECEDENCE,
+        ),
+        ConditionTree::Not(tree) => (
+            format!("not {}", print_tree(tree, NOT_PRECEDENCE)),
+            NOT_PRECEDENCE,
+        ),
+        ConditionTree::True => ("true".to_string(), ATOM_PRECEDENCE),
+        ConditionTree::False => ("false".to_string(), ATOM_PRECEDENCE),
+        ConditionTree::Field(condition) => (print_field(condition), ATOM_PRECEDENCE),
+    };
+
+    match precedence < parent_precedence {
+        true => format!("({text})"),
+        false => text,
+    }
+}
+
+fn print_field(condition: &FieldCondition) -> String {
+    let field = &condition.field;
+    match &condition.filter {
+        FieldFilter::Relation(trees) | FieldFilter::Exists(trees) => match trees.as_slice() {
+            [ConditionTree::Field(inner_condition)] => {
+                format!("{field}.{}", print_field(inner_condition))
+            }
+            [] => format!("{field}(true)"),
+            trees => format!("{field}({})", print_conditions(trees)),
+        },
+        FieldFilter::Comparison(operator, value) => {
+            format!("{field} {}", print_comparison(operator, value))
+        }
+    }
+}
+
+fn print_comparison(operator: &Operator, value: &Value) -> String {
+    let operator = match (operator, value) {
+        (Operator::IsNull, _) => return "is null".to_string(),
+        (Operator::IsNotNull, _) => return "is not null".to_string(),
+        (
+            Operator::Between,
+            Value::Range {
+                from,
+                to,
+                from_inclusive: true,
+                to_inclusive: true,
+            },
+        ) => return format!("between {} and {}", print_value(from), print_value(to)),
+        (Operator::Equal, _) => "=",
+        (Operator::NotEqual, _) => "!=",
+        (Operator::GreaterThan, _) => ">",
+        (Operator::LowerThan, _) => "<",
+        (Operator::GreaterOrEqual, _) => ">=",
+        (Operator::LowerOrEqual, _) => "<=",
+        (Operator::NotLike, _) => "not like",
+        (Operator::NotIn, _) => "not in",
+        (operator, _) => operator.name(),
+    };
+    format!("{operator} {}", print_value(value))
+}
+
+fn print_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Integer(value) => value.to_string(),
+        // Display is never in exponent form (that tokenizer doesn't read), with decimal point
+        // added for whole numbers to be parsed back as float, i.e. 1.0, and nan, inf and -inf
+        // for values that aren't finite
+        Value::Float(value) if value.is_nan() => "nan".to_string(),
+        Value::Float(value) if value.is_infinite() => match value.is_sign_positive() {
+            true => "inf".to_string(),
+            false => "-inf".to_string(),
+        },
+        Value::Float(value) => match value.to_string() {
+            value if value.contains('.') => value,
+            value => format!("{value}.0"),
+        },
+        Value::String(value) => format!(
+            "\"{}\"",
+            value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+                .replace('\t', "\\t")
+        ),
+        Value::List(values) => format!(
+            "({})",
+            values
+                .iter()
+                .map(print_value)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Value::Range {
+            from,
+            to,
+            from_inclusive,
+            to_inclusive,
+        } => format!(
+            "{}{}, {}{}",
+            if *from_inclusive { "[" } else { "(" },
+            print_value(from),
+            print_value(to),
+            if *to_inclusive { "]" } else { ")" }
+        ),
+        Value::Duration(number, unit) => format!("{number} {}", unit.name()),
+        Value::Period(period) => period.name().to_string(),
+    }
+}
+
+#[test]
+fn test() {
+    let parse = parse::<FieldCondition>;
+
+    // Printed back the same way
+    for input in [
+        r#"number_field >= 2 and (text_field like "4%" or bool_field = true) and double_field is null"#,
+        r#"not (a = 1 or b != "x") and c not in (1, 2.5) and d is not null"#,
+        r#"bike.color in ("orange", "purple") or bike(name starts_with "c" and color = "grey")"#,
+        r#"a between 1 and 2 and b between (1, 2] and c = "\"quoted\"""#,
+        "date last 2 days or date this week or date older_than 1 hours or true",
+    ] {
+        assert_eq!(print(&parse(input).unwrap()), input);
+    }
+
+    // Normalised
+    assert_eq!(
+        print(&parse("(a = 1 AND b <> 2) or NOT (c<'x') OR d IS NULL").unwrap()),
+        r#"a = 1 and b != 2 or not c < "x" or d is null"#
+    );
+    assert_eq!(parse("").unwrap(), vec![]);
+
+    let error = |input: &str| parse(input).unwrap_err();
+
+    assert_eq!(
+        error("a = 1 and (b = 2"),
+        SyntaxError::new(Some(16), SyntaxErrorKind::UnexpectedEnd)
+    );
+    assert_eq!(
+        error("a = 1 b = 2"),
+        SyntaxError::new(Some(6), SyntaxErrorKind::UnexpectedToken("b".to_string()))
+    );
+    assert_eq!(
+        error("a = 1 or b ~ 2"),
+        SyntaxError::new(Some(11), SyntaxErrorKind::UnexpectedCharacter('~'))
+    );
+    assert_eq!(
+        error(r#"a = "x"#),
+        SyntaxError::new(Some(4), SyntaxErrorKind::UnterminatedString)
+    );
+    assert_eq!(
+        error("a in (1, 2 b"),
+        SyntaxError::new(Some(11), SyntaxErrorKind::UnexpectedToken("b".to_string()))
+    );
+    assert_eq!(
+        error("a last 2 fortnights").to_string(),
+        "unexpected 'fortnights' at position 9"
+    );
+
+    // Nesting is limited, rather than overflowing the stack
+    assert_eq!(
+        error(&"(".repeat(100_000)),
+        SyntaxError::new(Some(MAX_DEPTH), SyntaxErrorKind::TooDeep)
+    );
+    assert_eq!(
+        error(&format!("{}a = 1", "not ".repeat(100_000))).kind,
+        SyntaxErrorKind::TooDeep
+    );
+    assert_eq!(
+        error(&format!("{}b = 1", "a.".repeat(100_000))).to_string(),
+        "nesting is deeper than 64 at position 129"
+    );
+    let nested = format!(
+        "{}a = 1{}",
+        "(".repeat(MAX_DEPTH - 1),
+        ")".repeat(MAX_DEPTH - 1)
+    );
+    assert_eq!(print(&parse(&nested).unwrap()), "a = 1");
+
+    // Large and small floats are printed without exponent
+    let conditions = vec![
+        ConditionTree::Field(FieldCondition {
+            field: "a".to_string(),
+            location: None,
+            filter: FieldFilter::Comparison(Operator::Equal, Value::Float(1e20)),
+        }),
+        ConditionTree::Field(FieldCondition {
+            field: "b".to_string(),
+            location: None,
+            filter: FieldFilter::Comparison(Operator::GreaterThan, Value::Float(-1.5e-7)),
+        }),
+    ];
+    let printed = print(&conditions);
+    assert_eq!(printed, "a = 100000000000000000000.0 and b > -0.00000015");
+    let parsed: Vec<SyntaxTree> = parse(&printed)
+        .unwrap()
+        .into_iter()
+        .map(|mut condition| {
+            if let ConditionTree::Field(condition) = &mut condition {
+                condition.location = None;
+            }
+            condition
+        })
+        .collect();
+    assert_eq!(parsed, conditions);
+
+    // NaN isn't equal to itself, so round trip is checked on printed text
+    let conditions: Vec<SyntaxTree> = [f64::NAN, f64::INFINITY, f64::NEG_INFINITY]
+        .into_iter()
+        .map(|value| {
+            ConditionTree::Field(FieldCondition {
+                field: "a".to_string(),
+                location: None,
+                filter: FieldFilter::Comparison(Operator::Equal, Value::Float(value)),
+            })
+        })
+        .collect();
+    let printed = print(&conditions);
+    assert_eq!(printed, "a = nan and a = inf and a = -inf");
+    assert_eq!(print(&parse(&printed).unwrap()), printed);
+    assert!(parse("a = -infinite").is_err());
+}